@@ -0,0 +1,216 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use std::path::PathBuf;
+// dependencies
+use chrono::NaiveDate;
+use clap::{ArgAction, Parser, Subcommand, ValueEnum};
+
+pub(crate) const EXIT_FAILURE: u8 = 1;
+
+const AFTER_HELP: &str = "\
+Exit codes:
+  0  success
+  1  the command failed (e.g. no matching task, invalid data, write error)
+  2  invalid command line usage";
+
+#[derive(Parser)]
+#[command(version, about, after_help = AFTER_HELP, disable_version_flag = true)]
+pub(crate) struct Cli {
+    #[command(subcommand)]
+    pub(crate) command: Option<Command>,
+
+    /// Print version
+    #[arg(short = 'v', long, action = ArgAction::Version)]
+    version: Option<bool>,
+
+    /// Log level (default: info, or warning for commands printing to the console)
+    #[arg(long, global = true, value_enum)]
+    pub(crate) log_level: Option<LogLevelArg>,
+
+    /// Shorthand for '--log-level debug'
+    #[arg(long, global = true)]
+    pub(crate) debug: bool,
+
+    /// Task data dir, instead of the one in the configuration
+    #[arg(long, global = true, value_name = "DIR")]
+    pub(crate) data_dir: Option<PathBuf>,
+
+    /// Reject task files with unknown (e.g. misspelled) fields instead of ignoring them
+    #[arg(long, global = true)]
+    pub(crate) strict: bool,
+
+    /// Simulated current date (YYYY-MM-DD), the output file is not updated
+    #[arg(long, global = true, value_name = "DATE", value_parser = parse_date)]
+    pub(crate) date: Option<NaiveDate>,
+}
+
+#[derive(Subcommand)]
+pub(crate) enum Command {
+    /// Update the output file only (default)
+    Export {
+        /// Output dir, instead of the one in the configuration
+        #[arg(long, value_name = "DIR")]
+        output_dir: Option<PathBuf>,
+    },
+    /// Print all dated tasks
+    Show,
+    /// Print the tasks for today
+    Today,
+    /// Run the interactive terminal interface
+    Tui,
+    /// Add a simple task item, creating the file of the prefix if needed
+    Add {
+        /// Prefix of the simple task file (or its file name)
+        prefix: String,
+        /// Title of the item
+        title: String,
+        /// Note of the item
+        #[arg(long, default_value = "")]
+        note: String,
+        /// Due date (YYYY-MM-DD), today if omitted
+        #[arg(long)]
+        due: Option<String>,
+        /// Time of day: Morning, Midday, Any or Evening
+        #[arg(long, default_value = "Any")]
+        time_of_day: String,
+    },
+    /// Create a new task definition
+    New {
+        #[command(subcommand)]
+        kind: Box<NewCommand>,
+    },
+    /// Move the done simple task items to the archive dir in the data dir
+    Archive {
+        /// Only the items done more than this number of days ago
+        #[arg(long, value_name = "DAYS")]
+        older_than: Option<u32>,
+    },
+    /// Validate all task files and print every problem (log level: none by default)
+    Check,
+    /// Rewrite task files in canonical form (key order, indentation, dates, order of days)
+    Fmt {
+        /// Only list the files that are not formatted, and fail if there are any
+        #[arg(long)]
+        check: bool,
+    },
+    /// Rewrite older task files in the current version, after a backup in the data dir
+    Migrate {
+        /// Only list the files to migrate
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Write a JSON Schema of each task type, for editors
+    Schema {
+        /// Dir of the schema files (<type>.schema.json)
+        output_dir: PathBuf,
+    },
+    /// Mark a recurring task done
+    Done {
+        /// File name or part of the title
+        task: String,
+        /// Completion date (YYYY-MM-DD), today if omitted
+        #[arg(value_parser = parse_date)]
+        date: Option<NaiveDate>,
+    },
+    /// Print the completion history of a recurring task
+    History {
+        /// File name or part of the title
+        task: String,
+        /// Also the done simple task items with the title, the archived ones included
+        #[arg(long)]
+        archive: bool,
+    },
+    /// Postpone a recurring task or a simple task item
+    Snooze {
+        /// File name or part of the title
+        task: String,
+        /// Date (YYYY-MM-DD) or number of days from today
+        #[arg(required_unless_present = "clear")]
+        until: Option<String>,
+        /// Remove the snooze instead
+        #[arg(long, conflicts_with = "until")]
+        clear: bool,
+    },
+}
+
+#[derive(Subcommand)]
+pub(crate) enum NewCommand {
+    /// Create a recurring task file, validated like when loading it
+    Recurring {
+        /// Title of the task, also used for the file name
+        title: String,
+        /// Number of intervals between occurrences
+        #[arg(long, default_value_t = 1)]
+        every: u8,
+        /// Interval: Day, Week, Month, Year or the name of one in the config, e.g. quarter
+        #[arg(long, required_unless_present = "rrule")]
+        interval: Option<String>,
+        /// RRULE subset instead of the interval, e.g. 'FREQ=MONTHLY;BYDAY=2TU'
+        #[arg(long, conflicts_with_all = ["interval", "every"])]
+        rrule: Option<String>,
+        /// Date (YYYY-MM-DD) the task was last done, today if omitted
+        #[arg(long)]
+        last: Option<String>,
+        /// Schedule the occurrences: from_completion or fixed
+        #[arg(long)]
+        schedule_mode: Option<String>,
+        /// Date (YYYY-MM-DD) of the first occurrence of a fixed schedule
+        #[arg(long)]
+        anchor: Option<String>,
+        /// Note of the task
+        #[arg(long, default_value = "")]
+        note: String,
+        /// Time of day: Morning, Midday, Any or Evening
+        #[arg(long)]
+        time_of_day: Option<String>,
+        /// Clock time (HH:MM) of the task
+        #[arg(long)]
+        time: Option<String>,
+        /// Estimated duration of the task in minutes
+        #[arg(long)]
+        duration_minutes: Option<u32>,
+        /// Move an overdue task to: Today or ToBeDetermined
+        #[arg(long)]
+        snap_to: Option<String>,
+        /// Move the task to the next weekday: Mon, Tue, Wed, Thu, Fri, Sat or Sun
+        #[arg(long)]
+        pivot_weekday: Option<String>,
+        /// First day (MM-DD) of the part of the year the task is active in
+        #[arg(long, requires = "season_to")]
+        season_from: Option<String>,
+        /// Last day (MM-DD) of the part of the year the task is active in
+        #[arg(long, requires = "season_from")]
+        season_to: Option<String>,
+        /// Last day (YYYY-MM-DD) an occurrence can fall on
+        #[arg(long)]
+        until: Option<String>,
+        /// Number of completions after which the task ends
+        #[arg(long)]
+        count: Option<u32>,
+        /// Number of days to show the task earlier
+        #[arg(long, allow_negative_numbers = true)]
+        buffer_days: Option<i32>,
+        /// Number of days before the due date to list the task today, keeping its due date
+        #[arg(long)]
+        lead_days: Option<u32>,
+        /// Title of a subtask (repeatable)
+        #[arg(long = "subtask", value_name = "TITLE")]
+        subtasks: Vec<String>,
+    },
+}
+
+#[derive(Clone, ValueEnum)]
+pub(crate) enum LogLevelArg {
+    None,
+    Error,
+    Warning,
+    Info,
+    Debug,
+}
+
+fn parse_date(text: &str) -> Result<NaiveDate, String> {
+    return NaiveDate::parse_from_str(text, "%Y-%m-%d")
+        .map_err(|why| format!("expected a date as YYYY-MM-DD ({})", why));
+}
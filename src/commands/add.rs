@@ -0,0 +1,30 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use std::path::{Path, PathBuf};
+// internal
+use crate::logging;
+use crate::tasks::types::type_simple;
+
+pub(crate) fn run(
+    data_dir_todo: &Path,
+    prefix: &str,
+    title: &str,
+    note: &str,
+    due: &str,
+    time_of_day: &str,
+) -> bool {
+    let dir_path: PathBuf = data_dir_todo.join(type_simple::DIR_NAME);
+    return match type_simple::add_item(&dir_path, prefix, title, note, due, time_of_day) {
+        None => false,
+        Some(file_path) => {
+            logging::info(format!(
+                "Added simple task '{}' to '{}'",
+                title,
+                file_path.display()
+            ));
+            true
+        }
+    };
+}
@@ -0,0 +1,74 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+// dependencies
+use chrono::{Days, NaiveDate};
+// internal
+use crate::logging;
+use crate::tasks::types::{self, type_simple};
+use crate::time;
+
+/// Next to the task type dirs, so that the archived items are not loaded
+const ARCHIVE_DIR_NAME: &str = "archive";
+
+/// The same file names and format as the task type dir, in a tree of its own
+pub(crate) fn archive_dir_path(data_dir_todo: &Path, dir_name: &str) -> PathBuf {
+    return data_dir_todo.join(ARCHIVE_DIR_NAME).join(dir_name);
+}
+
+pub(crate) fn run(data_dir_todo: &Path, older_than_days_opt: Option<u32>) -> bool {
+    let dir_path: PathBuf = data_dir_todo.join(type_simple::DIR_NAME);
+    if !dir_path.is_dir() {
+        logging::error(format!(
+            "Simple task dir '{}' not found",
+            dir_path.display()
+        ));
+        return false;
+    }
+    let archive_dir_path: PathBuf = archive_dir_path(data_dir_todo, type_simple::DIR_NAME);
+    if let Err(why) = fs::create_dir_all(&archive_dir_path) {
+        logging::error(format!(
+            "Couldn't create archive dir '{}' \n{}",
+            archive_dir_path.display(),
+            why
+        ));
+        return false;
+    }
+
+    let done_before_opt: Option<NaiveDate> =
+        older_than_days_opt.map(|day_count| time::today() - Days::new(day_count as u64));
+
+    let mut is_success: bool = true;
+    let mut archived_count: usize = 0;
+    for file_path in types::file_paths_sorted(&dir_path) {
+        let archive_file_path: PathBuf = match file_path.file_name() {
+            None => continue,
+            Some(file_name) => archive_dir_path.join(file_name),
+        };
+        match type_simple::archive_done_items(
+            &file_path,
+            &archive_file_path,
+            done_before_opt.as_ref(),
+        ) {
+            None => is_success = false,
+            Some(0) => {}
+            Some(count) => {
+                println!(
+                    "{}: {} item(s) archived to '{}'",
+                    file_path.display(),
+                    count,
+                    archive_file_path.display()
+                );
+                archived_count += count;
+            }
+        }
+    }
+
+    if archived_count == 0 {
+        println!("No items to archive");
+    }
+    return is_success;
+}
@@ -0,0 +1,31 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use std::path::Path;
+// internal
+use crate::tasks::data::TaskData;
+
+pub(crate) fn run(data_dir_todo: &Path) -> bool {
+    // the loaders report every problem they find instead of stopping at the first one
+    let mut task_data: TaskData = TaskData::load(data_dir_todo);
+    // stable, so that the problems of a file stay in order
+    task_data
+        .problems
+        .sort_by(|a, b| a.file_path.cmp(&b.file_path));
+
+    for problem in &task_data.problems {
+        println!("{}", problem);
+    }
+    return match task_data.problems.len() {
+        0 => {
+            println!("No problems found");
+            true
+        }
+        problem_count => {
+            println!();
+            println!("{} problem(s) found", problem_count);
+            false
+        }
+    };
+}
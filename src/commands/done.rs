@@ -0,0 +1,18 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use std::path::{Path, PathBuf};
+// dependencies
+use chrono::NaiveDate;
+// internal
+use crate::commands;
+use crate::tasks::types::type_recurring;
+
+pub(crate) fn run(data_dir_todo: &Path, query: &str, date: &NaiveDate) -> bool {
+    let file_path: PathBuf = match commands::find_recurring(data_dir_todo, query) {
+        None => return false,
+        Some(file_path) => file_path,
+    };
+    return type_recurring::mark_done(&file_path, date);
+}
@@ -0,0 +1,64 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+// internal
+use crate::logging;
+use crate::tasks::types;
+
+pub(crate) fn run(data_dir_todo: &Path, check: bool) -> bool {
+    let mut is_success: bool = true;
+    let mut changed_count: usize = 0;
+    for task_file_type in types::TASK_FILE_TYPES {
+        let dir_path: PathBuf = data_dir_todo.join(task_file_type.dir_name);
+        if !dir_path.is_dir() {
+            continue;
+        }
+
+        for file_path in types::file_paths_sorted(&dir_path) {
+            let text_canonical: String = match (task_file_type.format)(&file_path) {
+                None => {
+                    is_success = false;
+                    continue;
+                }
+                Some(text) => text,
+            };
+            let text: String = match fs::read_to_string(&file_path) {
+                Err(why) => {
+                    logging::error(format!(
+                        "Couldn't read todo file '{}' \n{}",
+                        file_path.display(),
+                        why
+                    ));
+                    is_success = false;
+                    continue;
+                }
+                Ok(text) => text,
+            };
+            if text == text_canonical {
+                continue;
+            }
+
+            changed_count += 1;
+            if check {
+                println!("{}: not formatted", file_path.display());
+                continue;
+            }
+            if let Err(why) = fs::write(&file_path, text_canonical) {
+                logging::error(format!(
+                    "Couldn't write todo file '{}' \n{}",
+                    file_path.display(),
+                    why
+                ));
+                is_success = false;
+                continue;
+            }
+            println!("{}: formatted", file_path.display());
+        }
+    }
+
+    // in check mode, any file to format is a failure
+    return is_success && !(check && changed_count > 0);
+}
@@ -0,0 +1,67 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use std::path::Path;
+// dependencies
+use chrono::NaiveDate;
+// internal
+use crate::commands::{self, archive};
+use crate::history;
+use crate::logging;
+use crate::tasks::types::{type_recurring, type_simple};
+
+pub(crate) fn run(data_dir_todo: &Path, query: &str, with_archive: bool) -> bool {
+    if !with_archive {
+        return match commands::find_recurring(data_dir_todo, query) {
+            None => false,
+            Some(file_path) => print_recurring(&file_path),
+        };
+    }
+
+    // every match is listed, the recurring tasks and the done simple task items
+    let mut is_found: bool = false;
+    for file_path in commands::find_recurring_all(data_dir_todo, query) {
+        if print_recurring(&file_path) {
+            println!();
+            is_found = true;
+        }
+    }
+
+    let mut dates: Vec<NaiveDate> =
+        type_simple::find_done_dates(&data_dir_todo.join(type_simple::DIR_NAME), query);
+    dates.extend(type_simple::find_done_dates(
+        &archive::archive_dir_path(data_dir_todo, type_simple::DIR_NAME),
+        query,
+    ));
+    dates.sort();
+    if !dates.is_empty() {
+        println!("Simple task items matching '{}'", query);
+        print_dates(&dates);
+        is_found = true;
+    }
+
+    if !is_found {
+        logging::error(format!("No task matching '{}'", query));
+    }
+    return is_found;
+}
+
+fn print_recurring(file_path: &Path) -> bool {
+    let (title, dates): (String, Vec<NaiveDate>) = match type_recurring::load_history(file_path) {
+        None => return false,
+        Some(title_and_dates) => title_and_dates,
+    };
+
+    println!("{}", title);
+    print_dates(&dates);
+    return true;
+}
+
+fn print_dates(dates: &[NaiveDate]) {
+    println!("{}", history::summary(dates));
+    println!();
+    for line in history::entries(dates) {
+        println!("- {}", line);
+    }
+}
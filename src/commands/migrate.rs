@@ -0,0 +1,109 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+// internal
+use crate::logging;
+use crate::tasks::types;
+use crate::time::timestamp;
+
+/// Not a task type dir, so it is never loaded
+const BACKUP_DIR_NAME: &str = ".backup";
+
+pub(crate) fn run(data_dir_todo: &Path, dry_run: bool) -> bool {
+    let backup_dir: PathBuf = data_dir_todo
+        .join(BACKUP_DIR_NAME)
+        .join(format!("migrate-{}", timestamp::current_for_file_name()));
+
+    let mut is_success: bool = true;
+    let mut migrated_count: usize = 0;
+    for task_file_type in types::TASK_FILE_TYPES {
+        let dir_path: PathBuf = data_dir_todo.join(task_file_type.dir_name);
+        if !dir_path.is_dir() {
+            continue;
+        }
+        let version_current: u32 = (task_file_type.version_current)();
+
+        for file_path in types::file_paths_sorted(&dir_path) {
+            let version: u32 = match types::version_on_disk(&file_path) {
+                Err(problem) => {
+                    logging::error(problem.to_string());
+                    is_success = false;
+                    continue;
+                }
+                Ok(version) => version,
+            };
+            if version == version_current {
+                continue;
+            }
+            if version > version_current {
+                logging::error(format!(
+                    "Version {} of '{}' is newer than the supported version {}",
+                    version,
+                    file_path.display(),
+                    version_current
+                ));
+                is_success = false;
+                continue;
+            }
+
+            println!(
+                "{}: version {} -> {}",
+                file_path.display(),
+                version,
+                version_current
+            );
+            if dry_run {
+                migrated_count += 1;
+                continue;
+            }
+            if !backup(&file_path, &backup_dir.join(task_file_type.dir_name)) {
+                is_success = false;
+                continue;
+            }
+            if !(task_file_type.rewrite)(&file_path) {
+                is_success = false;
+                continue;
+            }
+            migrated_count += 1;
+        }
+    }
+
+    match (migrated_count, dry_run) {
+        (0, _) => println!("No files to migrate"),
+        (_, true) => println!("{} file(s) to migrate", migrated_count),
+        (_, false) => println!(
+            "{} file(s) migrated, backup in '{}'",
+            migrated_count,
+            backup_dir.display()
+        ),
+    }
+    return is_success;
+}
+
+fn backup(file_path: &Path, backup_dir: &Path) -> bool {
+    if let Err(why) = fs::create_dir_all(backup_dir) {
+        logging::error(format!(
+            "Couldn't create backup dir '{}' \n{}",
+            backup_dir.display(),
+            why
+        ));
+        return false;
+    }
+    let backup_path: PathBuf = match file_path.file_name() {
+        None => return false,
+        Some(file_name) => backup_dir.join(file_name),
+    };
+    if let Err(why) = fs::copy(file_path, &backup_path) {
+        logging::error(format!(
+            "Couldn't back up '{}' to '{}' \n{}",
+            file_path.display(),
+            backup_path.display(),
+            why
+        ));
+        return false;
+    }
+    return true;
+}
@@ -0,0 +1,76 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+pub(crate) mod add;
+pub(crate) mod archive;
+pub(crate) mod check;
+pub(crate) mod done;
+pub(crate) mod fmt;
+pub(crate) mod history;
+pub(crate) mod migrate;
+pub(crate) mod new;
+pub(crate) mod schema;
+pub(crate) mod snooze;
+
+use std::path::{Path, PathBuf};
+// internal
+use crate::logging;
+use crate::tasks::task::origin::{TaskOrigin, TaskType};
+use crate::tasks::types::{type_recurring, type_simple};
+
+fn find_recurring_origins(data_dir_todo: &Path, query: &str) -> Vec<TaskOrigin> {
+    let dir_path: PathBuf = data_dir_todo.join(type_recurring::DIR_NAME);
+    return type_recurring::find(&dir_path, query)
+        .iter()
+        .map(|file_path| TaskOrigin::new(TaskType::Recurring, file_path))
+        .collect();
+}
+
+fn find_simple_origins(data_dir_todo: &Path, query: &str) -> Vec<TaskOrigin> {
+    let dir_path: PathBuf = data_dir_todo.join(type_simple::DIR_NAME);
+    return type_simple::find(&dir_path, query)
+        .iter()
+        .map(|(file_path, item_index)| {
+            TaskOrigin::new(TaskType::Simple, file_path).with_item(*item_index)
+        })
+        .collect();
+}
+
+fn select_one(query: &str, matches: Vec<TaskOrigin>) -> Option<TaskOrigin> {
+    return match matches.as_slice() {
+        [] => {
+            logging::error(format!("No task matching '{}'", query));
+            None
+        }
+        [origin] => Some(origin.clone()),
+        _ => {
+            logging::error(format!(
+                "Multiple tasks matching '{}', use the file name or a longer title instead:",
+                query
+            ));
+            for origin in &matches {
+                logging::error(format!("  {}", origin));
+            }
+            None
+        }
+    };
+}
+
+fn find_recurring_all(data_dir_todo: &Path, query: &str) -> Vec<PathBuf> {
+    return find_recurring_origins(data_dir_todo, query)
+        .into_iter()
+        .map(|origin| origin.file_path)
+        .collect();
+}
+
+fn find_recurring(data_dir_todo: &Path, query: &str) -> Option<PathBuf> {
+    let matches: Vec<TaskOrigin> = find_recurring_origins(data_dir_todo, query);
+    return select_one(query, matches).map(|origin| origin.file_path);
+}
+
+fn find_recurring_or_simple(data_dir_todo: &Path, query: &str) -> Option<TaskOrigin> {
+    let mut matches: Vec<TaskOrigin> = find_recurring_origins(data_dir_todo, query);
+    matches.extend(find_simple_origins(data_dir_todo, query));
+    return select_one(query, matches);
+}
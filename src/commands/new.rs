@@ -0,0 +1,122 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use std::path::{Path, PathBuf};
+// dependencies
+use serde_json::{json, Map, Value};
+// internal
+use crate::cli::NewCommand;
+use crate::logging;
+use crate::tasks::types::type_recurring;
+use crate::time;
+
+pub(crate) fn run(data_dir_todo: &Path, kind: &NewCommand) -> bool {
+    return match kind {
+        NewCommand::Recurring { .. } => run_recurring(data_dir_todo, kind),
+    };
+}
+
+fn run_recurring(data_dir_todo: &Path, kind: &NewCommand) -> bool {
+    let NewCommand::Recurring {
+        title,
+        every,
+        interval,
+        rrule,
+        last,
+        schedule_mode,
+        anchor,
+        note,
+        time_of_day,
+        time,
+        duration_minutes,
+        snap_to,
+        pivot_weekday,
+        season_from,
+        season_to,
+        until,
+        count,
+        buffer_days,
+        lead_days,
+        subtasks,
+    } = kind;
+
+    // the definition has the shape of the file, it is checked by the type itself
+    let mut definition: Map<String, Value> = Map::new();
+    definition.insert("title".to_string(), json!(title));
+    definition.insert("note".to_string(), json!(note));
+    if let Some(interval) = interval {
+        let interval: Value = match interval.as_str() {
+            "Day" | "Week" | "Month" | "Year" => json!(interval),
+            name => json!({ "Other": name }),
+        };
+        definition.insert(
+            "frequency".to_string(),
+            json!({"number": every, "interval": interval}),
+        );
+    }
+    if let Some(rrule) = rrule {
+        definition.insert("rrule".to_string(), json!(rrule));
+    }
+    let is_fixed: bool = schedule_mode.as_deref() == Some("fixed");
+    let last: String = match last {
+        // no occurrence of a fixed schedule is done yet
+        None if is_fixed => "".to_string(),
+        None => time::today().format("%Y-%m-%d").to_string(),
+        Some(last) => last.clone(),
+    };
+    definition.insert("last".to_string(), json!(last));
+    if let Some(schedule_mode) = schedule_mode {
+        definition.insert("schedule_mode".to_string(), json!(schedule_mode));
+    }
+    if let Some(anchor) = anchor {
+        definition.insert("anchor".to_string(), json!(anchor));
+    }
+    if let Some(time_of_day) = time_of_day {
+        definition.insert("time_of_day".to_string(), json!(time_of_day));
+    }
+    if let Some(time) = time {
+        definition.insert("time".to_string(), json!(time));
+    }
+    if let Some(duration_minutes) = duration_minutes {
+        definition.insert("duration_minutes".to_string(), json!(duration_minutes));
+    }
+    if let Some(snap_to) = snap_to {
+        definition.insert("snap_to".to_string(), json!(snap_to));
+    }
+    if let Some(weekday) = pivot_weekday {
+        definition.insert("pivot".to_string(), json!({"weekday": weekday}));
+    }
+    if let (Some(from), Some(to)) = (season_from, season_to) {
+        definition.insert("season".to_string(), json!({"from": from, "to": to}));
+    }
+    if let Some(until) = until {
+        definition.insert("until".to_string(), json!(until));
+    }
+    if let Some(count) = count {
+        definition.insert("count".to_string(), json!(count));
+    }
+    if let Some(buffer_days) = buffer_days {
+        definition.insert("buffer_days".to_string(), json!(buffer_days));
+    }
+    if let Some(lead_days) = lead_days {
+        definition.insert("lead_days".to_string(), json!(lead_days));
+    }
+    let subtasks: Vec<Value> = subtasks
+        .iter()
+        .map(|subtask| json!({"title": subtask, "done": ""}))
+        .collect();
+    definition.insert("subtasks".to_string(), Value::Array(subtasks));
+
+    let dir_path: PathBuf = data_dir_todo.join(type_recurring::DIR_NAME);
+    return match type_recurring::create(&dir_path, Value::Object(definition)) {
+        None => false,
+        Some(file_path) => {
+            logging::info(format!(
+                "Created recurring task file '{}'",
+                file_path.display()
+            ));
+            true
+        }
+    };
+}
@@ -0,0 +1,22 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use std::path::{Path, PathBuf};
+// dependencies
+use schemars::Schema;
+// internal
+use crate::logging;
+use crate::tasks::types;
+
+pub(crate) fn run(output_dir: &Path) -> bool {
+    let mut is_success: bool = true;
+    for task_file_type in types::TASK_FILE_TYPES {
+        let schema: Schema = (task_file_type.json_schema)(task_file_type.dir_name);
+        let file_path: PathBuf =
+            output_dir.join(format!("{}.schema.json", task_file_type.dir_name));
+        logging::info(format!("Writing JSON Schema '{}'", file_path.display()));
+        is_success &= types::save(&file_path, &schema);
+    }
+    return is_success;
+}
@@ -0,0 +1,28 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use std::path::Path;
+// dependencies
+use chrono::NaiveDate;
+// internal
+use crate::commands;
+use crate::logging;
+use crate::tasks::task::origin::TaskOrigin;
+use crate::tasks::types;
+
+pub(crate) fn run(data_dir_todo: &Path, query: &str, date_opt: Option<&NaiveDate>) -> bool {
+    let origin: TaskOrigin = match commands::find_recurring_or_simple(data_dir_todo, query) {
+        None => return false,
+        Some(origin) => origin,
+    };
+    match date_opt {
+        None => logging::info(format!("Clearing snooze of task at '{}'", origin)),
+        Some(date) => logging::info(format!(
+            "Snoozing task at '{}' until {}",
+            origin,
+            date.format("%Y-%m-%d")
+        )),
+    }
+    return types::set_snoozed_until(&origin, date_opt);
+}
@@ -2,6 +2,7 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at http://mozilla.org/MPL/2.0/.
 
+use std::collections::BTreeMap;
 use std::fs::File;
 use std::io::{Read, Stdin, Stdout, Write};
 use std::path::{Display, Path, PathBuf};
@@ -10,11 +11,14 @@ use std::{fs, io};
 use directories_next::ProjectDirs;
 // internal
 use crate::logging;
+use crate::tasks::task::meta::TaskFrequency;
 
 const DIRS_QUALIFIER: &str = "dev";
 const DIRS_ORGANIZATION: &str = "sunarch";
 
-fn load_dir() -> PathBuf {
+const NAMED_INTERVALS_FILE_NAME: &str = "named-intervals.json";
+
+pub(crate) fn load_dir() -> PathBuf {
     let project_dirs: ProjectDirs =
         ProjectDirs::from(DIRS_QUALIFIER, DIRS_ORGANIZATION, env!("CARGO_PKG_NAME"))
             .unwrap_or_else(|| panic!("Unable to load project directory paths!"));
@@ -41,7 +45,7 @@ fn load_data_dir_single(
     config_dir: &Path,
     config_file_name: &str,
     config_file_purpose: &str,
-) -> PathBuf {
+) -> Option<PathBuf> {
     let config_file_path: PathBuf = config_dir.join(config_file_name);
     let mut config_content_is_ok: bool = false;
     let mut data_dir: PathBuf = Default::default();
@@ -91,9 +95,17 @@ fn load_data_dir_single(
             input.clear();
             print!("Data dir path for '{config_file_purpose}': ");
             stdout.flush().expect("Flushing output unsuccessful.");
-            stdin
+            let byte_count: usize = stdin
                 .read_line(input)
                 .expect("Reading from input unsuccessful.");
+            // end of input, e.g. when run from a script
+            if byte_count == 0 {
+                println!();
+                logging::error(format!(
+                    "No data dir path given for '{config_file_purpose}'"
+                ));
+                return None;
+            }
 
             data_dir = PathBuf::from(String::clone(input).trim_end());
 
@@ -139,18 +151,77 @@ fn load_data_dir_single(
         "Loaded data dir for '{config_file_purpose}': '{data_dir_display}'"
     ));
 
-    return data_dir;
+    return Some(data_dir);
 }
 
-pub(crate) fn load_data_dirs() -> (PathBuf, PathBuf, PathBuf) {
-    let config_dir: PathBuf = load_dir();
+pub(crate) fn load_data_dir_todo(
+    config_dir: &Path,
+    data_dir_override: Option<PathBuf>,
+) -> Option<PathBuf> {
+    return match data_dir_override {
+        None => load_data_dir_single(config_dir, "data-dir-path-todo.txt", "ToDo"),
+        Some(data_dir) => Some(data_dir),
+    };
+}
 
-    let data_dir_todo: PathBuf =
-        load_data_dir_single(&config_dir, "data-dir-path-todo.txt", "ToDo");
-    let data_dir_todo_output: PathBuf =
-        load_data_dir_single(&config_dir, "data-dir-path-todo-output.txt", "ToDo output");
-    let data_dir_finances: PathBuf =
-        load_data_dir_single(&config_dir, "data-dir-path-finances.txt", "finances");
+pub(crate) fn load_data_dir_todo_output(
+    config_dir: &Path,
+    data_dir_override: Option<PathBuf>,
+) -> Option<PathBuf> {
+    return match data_dir_override {
+        None => load_data_dir_single(config_dir, "data-dir-path-todo-output.txt", "ToDo output"),
+        Some(data_dir) => Some(data_dir),
+    };
+}
 
-    return (data_dir_todo, data_dir_todo_output, data_dir_finances);
+/// Optional, e.g. `{"quarter": {"number": 3, "interval": "Month"}}`
+pub(crate) fn load_named_intervals(config_dir: &Path) -> BTreeMap<String, TaskFrequency> {
+    let config_file_path: PathBuf = config_dir.join(NAMED_INTERVALS_FILE_NAME);
+    if !config_file_path
+        .try_exists()
+        .expect("Checking config file existence unsuccessful.")
+    {
+        return Default::default();
+    }
+
+    let input: String = match fs::read_to_string(&config_file_path) {
+        Err(why) => {
+            panic!(
+                "Couldn't read config file for named intervals ({})\n{}",
+                config_file_path.display(),
+                why
+            );
+        }
+        Ok(input) => input,
+    };
+    let mut named_intervals: BTreeMap<String, TaskFrequency> = match serde_json::from_str(&input) {
+        Err(why) => {
+            panic!(
+                "Couldn't parse config file for named intervals ({})\n{}",
+                config_file_path.display(),
+                why
+            );
+        }
+        Ok(named_intervals) => named_intervals,
+    };
+
+    // named intervals are made of the built-in ones only
+    named_intervals.retain(|name, frequency| {
+        let is_valid: bool =
+            frequency.number.is_some_and(|number| number > 0) && frequency.interval.is_built_in();
+        if !is_valid {
+            logging::error(format!(
+                "Named interval '{}' needs a number and a built-in interval",
+                name
+            ));
+        }
+        return is_valid;
+    });
+
+    logging::info(format!(
+        "Loaded {} named interval(s) from '{}'",
+        named_intervals.len(),
+        config_file_path.display()
+    ));
+    return named_intervals;
 }
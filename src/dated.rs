@@ -10,6 +10,7 @@ use chrono::{NaiveDate, NaiveWeek};
 // internal
 use crate::tasks::data::TaskData;
 use crate::tasks::task::contents::TaskVisibility;
+use crate::tasks::task::origin::TaskOrigin;
 use crate::tasks::task::Task;
 use crate::time;
 use crate::time::timestamp;
@@ -177,12 +178,26 @@ fn print_task_list(task_list: &Vec<Task>, output_fn: &FnOutput, file_option: &mu
 
 fn print_task(task: &Task, output_fn: &FnOutput, file_option: &mut Option<File>) {
     let done_marker: &str = if task.contents.is_done { "x" } else { " " };
+    // origin is only written to the file output, where it is hidden when rendered
+    let origin_display = |origin: TaskOrigin, file_option: &Option<File>| -> String {
+        match file_option {
+            None => Default::default(),
+            Some(_) => format!(" <!-- {} -->", origin),
+        }
+    };
 
     match task.contents.visibility {
         TaskVisibility::Visible => {
-            output_fn(&format!("- [{}] {}", done_marker, task), file_option);
+            let origin: String = origin_display(task.origin.clone(), file_option);
+            output_fn(
+                &format!("- [{}] {}{}", done_marker, task, origin),
+                file_option,
+            );
+        }
+        TaskVisibility::Inactive => {
+            let origin: String = origin_display(task.origin.clone(), file_option);
+            output_fn(&format!("- {}{}", task, origin), file_option);
         }
-        TaskVisibility::Inactive => output_fn(&format!("- {}", task), file_option),
         TaskVisibility::Hidden => return,
     }
 
@@ -194,13 +209,21 @@ fn print_task(task: &Task, output_fn: &FnOutput, file_option: &mut Option<File>)
             format!(" ({})", subtask.note)
         };
 
+        let origin: String = origin_display(task.subtask_origin(subtask), file_option);
+
         match subtask.visibility {
             TaskVisibility::Visible => output_fn(
-                &format!("    - [{}] {}{}", done_marker, subtask.title, note),
+                &format!(
+                    "    - [{}] {}{}{}",
+                    done_marker, subtask.title, note, origin
+                ),
                 file_option,
             ),
             TaskVisibility::Inactive => output_fn(
-                &format!("    - ~~[{}] {}{}~~", done_marker, subtask.title, note),
+                &format!(
+                    "    - ~~[{}] {}{}~~{}",
+                    done_marker, subtask.title, note, origin
+                ),
                 file_option,
             ),
             TaskVisibility::Hidden => continue,
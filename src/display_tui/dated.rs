@@ -2,17 +2,21 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at http://mozilla.org/MPL/2.0/.
 
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, HashSet};
 use std::fmt::Display;
+use std::path::Path;
 // dependencies
 use chrono::{NaiveDate, NaiveWeek};
 use ratatui::layout::Alignment;
 use ratatui::style::{Modifier, Style, Stylize};
 use ratatui::text::{Line, Span};
-use ratatui::widgets::{block::Title, Block, Paragraph, ScrollbarState, Wrap};
+use ratatui::widgets::block::{Position, Title};
+use ratatui::widgets::{Block, Paragraph, ScrollbarState, Wrap};
 // internal
+use crate::history;
 use crate::tasks::data::TaskData;
 use crate::tasks::task::contents::TaskVisibility;
+use crate::tasks::task::origin::TaskOrigin;
 use crate::tasks::task::Task;
 use crate::time;
 use crate::time::timestamp;
@@ -20,7 +24,17 @@ use crate::words;
 
 const INITIAL_SCROLL: usize = 0;
 
+pub(super) struct Selectable {
+    pub(super) line_index: usize,
+    pub(super) origin: TaskOrigin,
+    pub(super) task_date: NaiveDate,
+}
+
 pub(super) struct DatedView {
+    pub(super) title: &'static str,
+    pub(super) lines: Vec<Line<'static>>,
+    pub(super) selectables: Vec<Selectable>,
+    pub(super) selected: usize,
     pub(super) content_length: usize,
     pub(super) vertical_scroll: usize,
     pub(super) scrollbar_state: ScrollbarState,
@@ -29,6 +43,10 @@ pub(super) struct DatedView {
 impl Default for DatedView {
     fn default() -> Self {
         return Self {
+            title: Default::default(),
+            lines: Default::default(),
+            selectables: Default::default(),
+            selected: Default::default(),
             content_length: Default::default(),
             vertical_scroll: Default::default(),
             scrollbar_state: Default::default(),
@@ -37,35 +55,56 @@ impl Default for DatedView {
 }
 
 impl DatedView {
-    pub(super) fn new(content_length: usize) -> Self {
+    pub(super) fn new(
+        title: &'static str,
+        lines: Vec<Line<'static>>,
+        selectables: Vec<Selectable>,
+    ) -> Self {
+        let content_length: usize = lines.len();
         return Self {
+            title,
+            lines,
+            selectables,
+            selected: Default::default(),
             content_length,
             vertical_scroll: INITIAL_SCROLL,
             scrollbar_state: ScrollbarState::new(content_length).position(INITIAL_SCROLL),
         };
     }
-}
 
-fn par<'a>(lines: Vec<Line<'a>>, title: &'static str) -> (Paragraph<'a>, DatedView) {
-    let line_count: usize = lines.len();
-    const PROGRAM_NAME: &str = env!("CARGO_PKG_NAME");
-    return (
-        Paragraph::new(lines)
+    pub(super) fn get_selected(&self) -> Option<&Selectable> {
+        return self.selectables.get(self.selected);
+    }
+
+    pub(super) fn par(&self, status: &str) -> Paragraph<'static> {
+        const PROGRAM_NAME: &str = env!("CARGO_PKG_NAME");
+
+        let mut lines: Vec<Line<'static>> = self.lines.clone();
+        if let Some(selectable) = self.get_selected() {
+            if let Some(line) = lines.get_mut(selectable.line_index) {
+                *line = line.clone().patch_style(Modifier::REVERSED);
+            }
+        }
+
+        let mut block: Block = Block::bordered()
+            .title(par_create_title(PROGRAM_NAME, Alignment::Left, false))
+            .title(par_create_title(self.title, Alignment::Center, true))
+            .title(par_create_title(words::QUIT_NOTE, Alignment::Right, false));
+        if !status.is_empty() {
+            block = block
+                .title(par_create_title(status, Alignment::Left, false).position(Position::Bottom));
+        }
+
+        return Paragraph::new(lines)
             .style(Style::new().white().on_black())
             .alignment(Alignment::Left)
             .wrap(Wrap { trim: false })
             .scroll((1, 0))
-            .block(
-                Block::bordered()
-                    .title(par_create_title(PROGRAM_NAME, Alignment::Left, false))
-                    .title(par_create_title(title, Alignment::Center, true))
-                    .title(par_create_title(words::QUIT_NOTE, Alignment::Right, false)),
-            ),
-        DatedView::new(line_count),
-    );
+            .block(block);
+    }
 }
 
-fn par_create_title(text: &str, alignment: Alignment, to_bold: bool) -> Title {
+fn par_create_title(text: &str, alignment: Alignment, to_bold: bool) -> Title<'static> {
     let mut line: Line = Line::from(format!("[ {} ]", text));
     if to_bold {
         line = line.bold();
@@ -73,17 +112,19 @@ fn par_create_title(text: &str, alignment: Alignment, to_bold: bool) -> Title {
     return Title::from(line).alignment(alignment);
 }
 
-pub(super) fn par_of_overdue(task_data: &TaskData) -> (Paragraph, DatedView) {
+pub(super) fn view_of_overdue(task_data: &TaskData) -> DatedView {
     let mut lines: Vec<Line> = Default::default();
+    let mut selectables: Vec<Selectable> = Default::default();
 
     // no heading for overdue section
-    add_section_general(&task_data.sections.overdue, &mut lines);
+    add_section_general(&task_data.sections.overdue, &mut lines, &mut selectables);
 
-    return par(lines, words::TITLE_OVERDUE);
+    return DatedView::new(words::TITLE_OVERDUE, lines, selectables);
 }
 
-pub(super) fn par_of_today(task_data: &TaskData) -> (Paragraph, DatedView) {
+pub(super) fn view_of_today(task_data: &TaskData) -> DatedView {
     let mut lines: Vec<Line> = Default::default();
+    let mut selectables: Vec<Selectable> = Default::default();
 
     {
         let heading: String = format!(
@@ -93,7 +134,12 @@ pub(super) fn par_of_today(task_data: &TaskData) -> (Paragraph, DatedView) {
         );
         add_section_heading(heading.as_str(), &mut lines);
     }
-    add_section_list(&task_data.sections.today, &mut lines);
+    add_section_list(
+        &task_data.sections.today,
+        &task_data.dates.today,
+        &mut lines,
+        &mut selectables,
+    );
 
     if task_data.sections.today.is_empty() {
         add_empty_line(&mut lines);
@@ -108,25 +154,32 @@ pub(super) fn par_of_today(task_data: &TaskData) -> (Paragraph, DatedView) {
         );
     }
 
-    return par(lines, words::TITLE_TODAY);
+    return DatedView::new(words::TITLE_TODAY, lines, selectables);
 }
 
-pub(super) fn par_of_rest_of_the_week(task_data: &TaskData) -> (Paragraph, DatedView) {
+pub(super) fn view_of_rest_of_the_week(task_data: &TaskData) -> DatedView {
     let mut lines: Vec<Line> = Default::default();
+    let mut selectables: Vec<Selectable> = Default::default();
 
-    add_section_general(&task_data.sections.rest_of_the_week, &mut lines);
+    add_section_general(
+        &task_data.sections.rest_of_the_week,
+        &mut lines,
+        &mut selectables,
+    );
 
-    return par(lines, words::TITLE_REST_OF_THE_WEEK);
+    return DatedView::new(words::TITLE_REST_OF_THE_WEEK, lines, selectables);
 }
 
-pub(super) fn par_of_later_and_other(task_data: &TaskData) -> (Paragraph, DatedView) {
+pub(super) fn view_of_later_and_other(task_data: &TaskData) -> DatedView {
     let mut lines: Vec<Line> = Default::default();
+    let mut selectables: Vec<Selectable> = Default::default();
 
     add_section_heading(task_data.dates.current_year, &mut lines);
     add_section_dated(
         &task_data.sections.dated,
         &task_data.dates.dated_weeks_current_year,
         &mut lines,
+        &mut selectables,
     );
 
     add_section_heading(task_data.dates.next_year, &mut lines);
@@ -134,15 +187,64 @@ pub(super) fn par_of_later_and_other(task_data: &TaskData) -> (Paragraph, DatedV
         &task_data.sections.dated,
         &task_data.dates.dated_weeks_next_year,
         &mut lines,
+        &mut selectables,
     );
 
     add_section_heading(words::LATER, &mut lines);
-    add_section_general(&task_data.sections.later, &mut lines);
+    add_section_general(&task_data.sections.later, &mut lines, &mut selectables);
 
     add_section_heading(words::INACTIVE, &mut lines);
-    add_section_list(&task_data.sections.inactive, &mut lines);
+    add_section_list(
+        &task_data.sections.inactive,
+        &task_data.dates.today,
+        &mut lines,
+        &mut selectables,
+    );
 
-    return par(lines, words::TITLE_LATER_AND_OTHER);
+    return DatedView::new(words::TITLE_LATER_AND_OTHER, lines, selectables);
+}
+
+pub(super) fn view_of_history(task_data: &TaskData) -> DatedView {
+    let mut lines: Vec<Line> = Default::default();
+    let mut selectables: Vec<Selectable> = Default::default();
+
+    // a task with several occurrences is listed once, with the first one
+    let mut file_paths: HashSet<&Path> = HashSet::new();
+    let mut task_list: Vec<(&NaiveDate, &Task)> = task_data
+        .sections
+        .iter_all(&task_data.dates.today)
+        .filter(|(_, task)| !task.meta.history.is_empty())
+        .filter(|(_, task)| file_paths.insert(task.origin.file_path.as_path()))
+        .collect();
+    task_list.sort_by(|(_, task_a), (_, task_b)| {
+        task_a
+            .contents
+            .title
+            .to_ascii_lowercase()
+            .cmp(&task_b.contents.title.to_ascii_lowercase())
+    });
+
+    for (task_date, task) in task_list {
+        add_empty_line(&mut lines);
+        lines.push(Line::from(vec![Span::styled(
+            task.contents.to_string(),
+            Modifier::BOLD,
+        )]));
+        add_selectable(task.origin.clone(), task_date, &lines, &mut selectables);
+        lines.push(Line::from(history::summary(&task.meta.history)));
+        for entry in history::entries(&task.meta.history) {
+            lines.push(Line::from(format!("- {}", entry)));
+        }
+    }
+
+    if lines.is_empty() {
+        add_empty_line(&mut lines);
+        lines.push(
+            Line::from(vec![Span::styled(words::NOTE_NO_HISTORY, Modifier::BOLD)]).centered(),
+        );
+    }
+
+    return DatedView::new(words::TITLE_HISTORY, lines, selectables);
 }
 
 fn add_section_heading<T: Display>(text: T, lines: &mut Vec<Line>) {
@@ -172,24 +274,34 @@ fn add_day_heading(date: &NaiveDate, lines: &mut Vec<Line>) {
     )]));
 }
 
-fn add_section_general(task_map: &BTreeMap<NaiveDate, Vec<Task>>, lines: &mut Vec<Line>) {
+fn add_section_general(
+    task_map: &BTreeMap<NaiveDate, Vec<Task>>,
+    lines: &mut Vec<Line>,
+    selectables: &mut Vec<Selectable>,
+) {
     for (task_date, task_list) in task_map {
         add_day_heading(task_date, lines);
-        add_task_list(task_list, lines);
+        add_task_list(task_list, task_date, lines, selectables);
     }
 }
 
-fn add_section_list(task_list: &Vec<Task>, lines: &mut Vec<Line>) {
+fn add_section_list(
+    task_list: &Vec<Task>,
+    task_date: &NaiveDate,
+    lines: &mut Vec<Line>,
+    selectables: &mut Vec<Selectable>,
+) {
     if task_list.is_empty() {
         return;
     }
-    add_task_list(task_list, lines);
+    add_task_list(task_list, task_date, lines, selectables);
 }
 
 fn add_section_dated(
     task_map: &BTreeMap<NaiveDate, Vec<Task>>,
     week_list: &Vec<NaiveWeek>,
     lines: &mut Vec<Line>,
+    selectables: &mut Vec<Selectable>,
 ) {
     for week in week_list {
         add_week_heading(&week.first_day(), lines);
@@ -197,29 +309,58 @@ fn add_section_dated(
         for day in time::iterate_week(week) {
             if let Some((_, task_list)) = task_map.get_key_value(&day) {
                 add_day_heading(&day, lines);
-                add_task_list(task_list, lines);
+                add_task_list(task_list, &day, lines, selectables);
             }
         }
     }
 }
 
-fn add_task_list(task_list: &Vec<Task>, lines: &mut Vec<Line>) {
+fn add_task_list(
+    task_list: &Vec<Task>,
+    task_date: &NaiveDate,
+    lines: &mut Vec<Line>,
+    selectables: &mut Vec<Selectable>,
+) {
     add_empty_line(lines);
     for task in task_list {
-        add_task(task, lines);
+        add_task(task, task_date, lines, selectables);
     }
 }
 
-fn add_task(task: &Task, lines: &mut Vec<Line>) {
+fn add_selectable(
+    origin: TaskOrigin,
+    task_date: &NaiveDate,
+    lines: &[Line],
+    selectables: &mut Vec<Selectable>,
+) {
+    selectables.push(Selectable {
+        line_index: lines.len().saturating_sub(1),
+        origin,
+        task_date: *task_date,
+    });
+}
+
+fn add_task(
+    task: &Task,
+    task_date: &NaiveDate,
+    lines: &mut Vec<Line>,
+    selectables: &mut Vec<Selectable>,
+) {
     let done_marker: &str = if task.contents.is_done { "x" } else { " " };
 
     match task.contents.visibility {
-        TaskVisibility::Visible => lines.push(Line::from(format!(
-            "- [{}] {} {}",
-            done_marker,
-            task.meta.format_as_table_row(),
-            task.contents
-        ))),
+        TaskVisibility::Visible => {
+            lines.push(Line::from(format!(
+                "- [{}] {} {}",
+                done_marker,
+                task.meta.format_as_table_row(),
+                task.contents
+            )));
+            // a projected occurrence cannot be done ahead of the ones before it
+            if !task.meta.projected {
+                add_selectable(task.origin.clone(), task_date, lines, selectables);
+            }
+        }
         TaskVisibility::Inactive => lines.push(Line::from(format!(
             "- {} {}",
             task.meta.format_as_table_row(),
@@ -230,7 +371,7 @@ fn add_task(task: &Task, lines: &mut Vec<Line>) {
         }
     }
 
-    const SUBTASK_INDENT: usize = 24; // manual from meta width
+    const SUBTASK_INDENT: usize = 36; // manual from meta width
     for subtask in &task.meta.subtasks {
         let done_marker: &str = if subtask.is_done { "x" } else { " " };
         let note: String = if subtask.note.is_empty() {
@@ -240,13 +381,16 @@ fn add_task(task: &Task, lines: &mut Vec<Line>) {
         };
 
         match subtask.visibility {
-            TaskVisibility::Visible => lines.push(Line::from(format!(
-                "{}- [{}] {}{}",
-                " ".repeat(SUBTASK_INDENT),
-                done_marker,
-                subtask.title,
-                note
-            ))),
+            TaskVisibility::Visible => {
+                lines.push(Line::from(format!(
+                    "{}- [{}] {}{}",
+                    " ".repeat(SUBTASK_INDENT),
+                    done_marker,
+                    subtask.title,
+                    note
+                )));
+                add_selectable(task.subtask_origin(subtask), task_date, lines, selectables);
+            }
             TaskVisibility::Inactive => lines.push(Line::from(format!(
                 "{}- ~~[{}] {}{}~~",
                 " ".repeat(SUBTASK_INDENT),
@@ -5,10 +5,11 @@
 pub(crate) mod dated;
 mod tui_current_view;
 
-use std::collections::HashMap;
 use std::io;
+use std::path::{Path, PathBuf};
 use std::time::Duration;
 // dependencies
+use chrono::NaiveDate;
 use ratatui::crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind};
 use ratatui::layout::{Margin, Rect};
 use ratatui::widgets::{
@@ -17,25 +18,35 @@ use ratatui::widgets::{
 use ratatui::DefaultTerminal;
 use ratatui::Frame;
 // internal
-use crate::display_tui::dated::DatedView;
+use crate::display_tui::dated::{DatedView, Selectable};
 use crate::display_tui::tui_current_view::CurrentView;
 use crate::logging;
 use crate::tasks::data::TaskData;
+use crate::tasks::types;
+use crate::time;
+use crate::time::timestamp;
+use crate::words;
 
 const EVENT_POLL_TIMEOUT: Duration = Duration::from_millis(16);
 const DEFAULT_SCROLL_AMOUNT: usize = 2;
 const DEFAULT_SCROLL_PG_OVERLAP: usize = DEFAULT_SCROLL_AMOUNT + 2;
+const BORDER_HEIGHT: usize = 2;
 
-pub(crate) fn run(task_data: &TaskData) -> Result<(), io::Error> {
+pub(crate) fn run(data_dir_todo: &Path, task_data: TaskData) -> Result<TaskData, io::Error> {
     logging::info("Running TUI ...".to_string());
     let terminal: DefaultTerminal = ratatui::init();
-    let tui_result: Result<(), io::Error> = Tui::new().run(terminal, task_data);
+    let mut tui: Tui = Tui::new(data_dir_todo, task_data);
+    let tui_result: Result<(), io::Error> = tui.run(terminal);
     ratatui::restore();
     logging::info("Exiting TUI ...".to_string());
-    tui_result
+    tui_result.map(|_| tui.task_data)
 }
 
 struct Tui {
+    data_dir_todo: PathBuf,
+    task_data: TaskData,
+    status: String,
+
     current_view: CurrentView,
     current_height: u16,
 
@@ -43,11 +54,16 @@ struct Tui {
     view_today: DatedView,
     view_rest_of_the_week: DatedView,
     view_later_and_other: DatedView,
+    view_history: DatedView,
 }
 
 impl Tui {
-    fn new() -> Self {
-        return Self {
+    fn new(data_dir_todo: &Path, task_data: TaskData) -> Self {
+        let mut tui: Tui = Self {
+            data_dir_todo: data_dir_todo.to_path_buf(),
+            task_data,
+            status: Default::default(),
+
             current_view: Default::default(),
             current_height: 1,
 
@@ -55,7 +71,50 @@ impl Tui {
             view_today: Default::default(),
             view_rest_of_the_week: Default::default(),
             view_later_and_other: Default::default(),
+            view_history: Default::default(),
         };
+        tui.build_views();
+        return tui;
+    }
+
+    fn build_views(&mut self) {
+        self.view_overdue = dated::view_of_overdue(&self.task_data);
+        self.view_today = dated::view_of_today(&self.task_data);
+        self.view_rest_of_the_week = dated::view_of_rest_of_the_week(&self.task_data);
+        self.view_later_and_other = dated::view_of_later_and_other(&self.task_data);
+        self.view_history = dated::view_of_history(&self.task_data);
+    }
+
+    fn rebuild_views(&mut self) {
+        let selected_list: [usize; 5] = [
+            self.view_overdue.selected,
+            self.view_today.selected,
+            self.view_rest_of_the_week.selected,
+            self.view_later_and_other.selected,
+            self.view_history.selected,
+        ];
+        let scroll_list: [usize; 5] = [
+            self.view_overdue.vertical_scroll,
+            self.view_today.vertical_scroll,
+            self.view_rest_of_the_week.vertical_scroll,
+            self.view_later_and_other.vertical_scroll,
+            self.view_history.vertical_scroll,
+        ];
+
+        self.build_views();
+
+        let views: [&mut DatedView; 5] = [
+            &mut self.view_overdue,
+            &mut self.view_today,
+            &mut self.view_rest_of_the_week,
+            &mut self.view_later_and_other,
+            &mut self.view_history,
+        ];
+        for ((view, selected), scroll) in views.into_iter().zip(selected_list).zip(scroll_list) {
+            view.selected = selected.min(view.selectables.len().saturating_sub(1));
+            view.vertical_scroll = scroll.min(view.content_length.saturating_sub(1));
+            view.scrollbar_state = view.scrollbar_state.position(view.vertical_scroll);
+        }
     }
 
     fn current_view_set(&mut self, new_current_view: CurrentView) {
@@ -76,6 +135,7 @@ impl Tui {
             CurrentView::Today => &mut self.view_today,
             CurrentView::RestOfTheWeek => &mut self.view_rest_of_the_week,
             CurrentView::LaterAndOther => &mut self.view_later_and_other,
+            CurrentView::History => &mut self.view_history,
         };
     }
 
@@ -137,30 +197,93 @@ impl Tui {
         self.scroll_end(ScrollDirection::Backward);
     }
 
-    fn run(
-        &mut self,
-        mut terminal: DefaultTerminal,
-        task_data: &TaskData,
-    ) -> Result<(), io::Error> {
-        let par_of_overdue: Paragraph;
-        let par_of_today: Paragraph;
-        let par_of_rest_of_the_week: Paragraph;
-        let par_of_later_and_other: Paragraph;
-
-        (par_of_overdue, self.view_overdue) = dated::par_of_overdue(task_data);
-        (par_of_today, self.view_today) = dated::par_of_today(task_data);
-        (par_of_rest_of_the_week, self.view_rest_of_the_week) =
-            dated::par_of_rest_of_the_week(task_data);
-        (par_of_later_and_other, self.view_later_and_other) =
-            dated::par_of_later_and_other(task_data);
-
-        let mut par_map: HashMap<CurrentView, &Paragraph> = Default::default();
-        par_map.insert(CurrentView::Overdue, &par_of_overdue);
-        par_map.insert(CurrentView::Today, &par_of_today);
-        par_map.insert(CurrentView::RestOfTheWeek, &par_of_rest_of_the_week);
-        par_map.insert(CurrentView::LaterAndOther, &par_of_later_and_other);
-
-        terminal.draw(|frame: &mut Frame| self.draw(frame, &par_map))?;
+    fn select(&mut self, direction: ScrollDirection) {
+        let visible_height: usize = (self.current_height as usize).saturating_sub(BORDER_HEIGHT);
+        let view: &mut DatedView = self.get_view();
+        if view.selectables.is_empty() {
+            match direction {
+                ScrollDirection::Forward => self.scroll_down(),
+                ScrollDirection::Backward => self.scroll_up(),
+            }
+            return;
+        }
+
+        view.selected = match direction {
+            ScrollDirection::Forward => view
+                .selected
+                .saturating_add(1)
+                .min(view.selectables.len() - 1),
+            ScrollDirection::Backward => view.selected.saturating_sub(1),
+        };
+
+        // keep the selected line in sight, the first content line is hidden by the border
+        let line_index: usize = view.selectables[view.selected].line_index;
+        if line_index < view.vertical_scroll + 1 {
+            view.vertical_scroll = line_index.saturating_sub(1);
+        } else if line_index >= view.vertical_scroll + visible_height {
+            view.vertical_scroll = line_index + 1 - visible_height;
+        }
+        view.scrollbar_state = view.scrollbar_state.position(view.vertical_scroll);
+    }
+
+    fn select_next(&mut self) {
+        self.select(ScrollDirection::Forward);
+    }
+
+    fn select_prev(&mut self) {
+        self.select(ScrollDirection::Backward);
+    }
+
+    fn toggle_selected(&mut self) {
+        let today = self.task_data.dates.today;
+        let selectable: &Selectable = match self.get_view().get_selected() {
+            None => return,
+            Some(selectable) => selectable,
+        };
+
+        self.status = if types::toggle_done(&selectable.origin, &selectable.task_date, &today) {
+            format!("{} {}", words::STATUS_TOGGLED, selectable.origin)
+        } else {
+            format!("{} {}", words::STATUS_TOGGLE_FAILED, selectable.origin)
+        };
+
+        self.task_data = TaskData::load(&self.data_dir_todo);
+        self.rebuild_views();
+    }
+
+    fn snooze_selected(&mut self, to_clear: bool) {
+        let today = self.task_data.dates.today;
+        let selectable: &Selectable = match self.get_view().get_selected() {
+            None => return,
+            Some(selectable) => selectable,
+        };
+
+        // each snooze pushes the task one more day after its current date
+        let date_opt: Option<NaiveDate> = match to_clear {
+            true => None,
+            false => Some(time::increment_by_one_day(&selectable.task_date.max(today))),
+        };
+
+        self.status = if types::set_snoozed_until(&selectable.origin, date_opt.as_ref()) {
+            match date_opt {
+                None => format!("{} {}", words::STATUS_UNSNOOZED, selectable.origin),
+                Some(date) => format!(
+                    "{} {} {}",
+                    words::STATUS_SNOOZED,
+                    timestamp::day_short(&date),
+                    selectable.origin
+                ),
+            }
+        } else {
+            format!("{} {}", words::STATUS_SNOOZE_FAILED, selectable.origin)
+        };
+
+        self.task_data = TaskData::load(&self.data_dir_todo);
+        self.rebuild_views();
+    }
+
+    fn run(&mut self, mut terminal: DefaultTerminal) -> Result<(), io::Error> {
+        terminal.draw(|frame: &mut Frame| self.draw(frame))?;
 
         loop {
             if event::poll(EVENT_POLL_TIMEOUT)? {
@@ -170,10 +293,12 @@ impl Tui {
                         if to_quit {
                             break;
                         }
-                        terminal.draw(|frame: &mut Frame| self.draw(frame, &par_map))?;
+                        // loading and saving may have logged to the terminal
+                        terminal.clear()?;
+                        terminal.draw(|frame: &mut Frame| self.draw(frame))?;
                     }
                     Event::Resize(_, _) => {
-                        terminal.draw(|frame: &mut Frame| self.draw(frame, &par_map))?;
+                        terminal.draw(|frame: &mut Frame| self.draw(frame))?;
                     }
                     _ => {}
                 }
@@ -185,12 +310,13 @@ impl Tui {
 
     fn handle_key_event(&mut self, key_event: KeyEvent) -> bool {
         if key_event.kind == KeyEventKind::Press {
+            self.status.clear();
             match key_event.code {
                 KeyCode::Char('q') => return true,
 
                 KeyCode::Char('h') | KeyCode::Left => self.current_view_prev(),
-                KeyCode::Char('j') | KeyCode::Down => self.scroll_down(),
-                KeyCode::Char('k') | KeyCode::Up => self.scroll_up(),
+                KeyCode::Char('j') | KeyCode::Down => self.select_next(),
+                KeyCode::Char('k') | KeyCode::Up => self.select_prev(),
                 KeyCode::Char('l') | KeyCode::Right => self.current_view_next(),
 
                 KeyCode::End => self.scroll_bottom(),
@@ -203,9 +329,14 @@ impl Tui {
                 KeyCode::Char('2') => self.current_view_set(CurrentView::Today),
                 KeyCode::Char('3') => self.current_view_set(CurrentView::RestOfTheWeek),
                 KeyCode::Char('4') => self.current_view_set(CurrentView::LaterAndOther),
+                KeyCode::Char('5') => self.current_view_set(CurrentView::History),
 
                 KeyCode::Char('t') => self.current_view_set(CurrentView::Today),
 
+                KeyCode::Char(' ') | KeyCode::Char('x') | KeyCode::Enter => self.toggle_selected(),
+                KeyCode::Char('s') => self.snooze_selected(false),
+                KeyCode::Char('S') => self.snooze_selected(true),
+
                 _ => {}
             }
         }
@@ -213,30 +344,26 @@ impl Tui {
         return false;
     }
 
-    fn draw(&mut self, frame: &mut Frame, par_map: &HashMap<CurrentView, &Paragraph>) {
+    fn draw(&mut self, frame: &mut Frame) {
         let area: Rect = frame.area();
         self.current_height = area.height;
 
-        self.render_paragraph(frame, area, par_map);
+        self.render_paragraph(frame, area);
         self.render_scrollbar(frame, area);
     }
 
-    fn render_paragraph(
-        &mut self,
-        frame: &mut Frame,
-        area: Rect,
-        par_map: &HashMap<CurrentView, &Paragraph>,
-    ) {
-        let paragraph: &Paragraph = par_map
-            .get(&self.current_view)
-            .expect("Unable to get Paragraph from map of references");
-        let vertical_scroll: usize = match self.current_view {
-            CurrentView::Overdue => self.view_overdue.vertical_scroll,
-            CurrentView::Today => self.view_today.vertical_scroll,
-            CurrentView::RestOfTheWeek => self.view_rest_of_the_week.vertical_scroll,
-            CurrentView::LaterAndOther => self.view_later_and_other.vertical_scroll,
+    fn render_paragraph(&mut self, frame: &mut Frame, area: Rect) {
+        let status: String = if self.status.is_empty() {
+            match self.get_view().get_selected() {
+                None => Default::default(),
+                Some(selectable) => selectable.origin.to_string(),
+            }
+        } else {
+            self.status.clone()
         };
-        frame.render_widget(paragraph.clone().scroll((vertical_scroll as u16, 0)), area);
+        let view: &DatedView = self.get_view();
+        let paragraph: Paragraph = view.par(&status);
+        frame.render_widget(paragraph.scroll((view.vertical_scroll as u16, 0)), area);
     }
 
     fn render_scrollbar(&mut self, frame: &mut Frame, area: Rect) {
@@ -245,6 +372,7 @@ impl Tui {
             CurrentView::Today => &mut self.view_today.scrollbar_state,
             CurrentView::RestOfTheWeek => &mut self.view_rest_of_the_week.scrollbar_state,
             CurrentView::LaterAndOther => &mut self.view_later_and_other.scrollbar_state,
+            CurrentView::History => &mut self.view_history.scrollbar_state,
         };
 
         frame.render_stateful_widget(
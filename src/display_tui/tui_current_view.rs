@@ -8,6 +8,7 @@ pub(super) enum CurrentView {
     Today,
     RestOfTheWeek,
     LaterAndOther,
+    History,
 }
 
 impl Default for CurrentView {
@@ -23,6 +24,7 @@ impl CurrentView {
             CurrentView::Today => CurrentView::Overdue,
             CurrentView::RestOfTheWeek => CurrentView::Today,
             CurrentView::LaterAndOther => CurrentView::RestOfTheWeek,
+            CurrentView::History => CurrentView::LaterAndOther,
         };
     }
 
@@ -31,7 +33,8 @@ impl CurrentView {
             CurrentView::Overdue => CurrentView::Today,
             CurrentView::Today => CurrentView::RestOfTheWeek,
             CurrentView::RestOfTheWeek => CurrentView::LaterAndOther,
-            CurrentView::LaterAndOther => self.clone(),
+            CurrentView::LaterAndOther => CurrentView::History,
+            CurrentView::History => self.clone(),
         };
     }
 }
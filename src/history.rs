@@ -0,0 +1,40 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+// dependencies
+use chrono::NaiveDate;
+// internal
+use crate::time::timestamp;
+
+pub(crate) fn summary(dates: &[NaiveDate]) -> String {
+    let plural: &str = if dates.len() == 1 { "" } else { "s" };
+    return match (dates.first(), dates.last()) {
+        (Some(first), Some(last)) if dates.len() > 1 => {
+            let days_average: i64 = (*last - *first).num_days() / (dates.len() as i64 - 1);
+            format!(
+                "{} completion{}, every {} days on average",
+                dates.len(),
+                plural,
+                days_average
+            )
+        }
+        (_, _) => format!("{} completion{}", dates.len(), plural),
+    };
+}
+
+pub(crate) fn entries(dates: &[NaiveDate]) -> Vec<String> {
+    let mut entries: Vec<String> = Default::default();
+    for (index, date) in dates.iter().enumerate().rev() {
+        let entry: String = match index.checked_sub(1).and_then(|prev| dates.get(prev)) {
+            None => timestamp::day(date),
+            Some(date_previous) => format!(
+                "{}  +{} days",
+                timestamp::day(date),
+                (*date - *date_previous).num_days()
+            ),
+        };
+        entries.push(entry);
+    }
+    return entries;
+}
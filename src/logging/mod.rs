@@ -16,11 +16,26 @@ const MSG_LOCK_FAIL_WRITE: &str = "Failed to acquire lock to write log level.";
 
 static LOG_LEVEL: RwLock<LogLevel> = RwLock::new(LogLevel::Info);
 
+pub fn set_none() {
+    let mut lock: RwLockWriteGuard<LogLevel> = LOG_LEVEL.write().expect(MSG_LOCK_FAIL_WRITE);
+    *lock = LogLevel::None;
+}
+
+pub fn set_error() {
+    let mut lock: RwLockWriteGuard<LogLevel> = LOG_LEVEL.write().expect(MSG_LOCK_FAIL_WRITE);
+    *lock = LogLevel::Error;
+}
+
 pub fn set_warning() {
     let mut lock: RwLockWriteGuard<LogLevel> = LOG_LEVEL.write().expect(MSG_LOCK_FAIL_WRITE);
     *lock = LogLevel::Warning;
 }
 
+pub fn set_info() {
+    let mut lock: RwLockWriteGuard<LogLevel> = LOG_LEVEL.write().expect(MSG_LOCK_FAIL_WRITE);
+    *lock = LogLevel::Info;
+}
+
 pub fn set_debug() {
     let mut lock: RwLockWriteGuard<LogLevel> = LOG_LEVEL.write().expect(MSG_LOCK_FAIL_WRITE);
     *lock = LogLevel::Debug;
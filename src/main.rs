@@ -2,76 +2,207 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at http://mozilla.org/MPL/2.0/.
 
+mod cli;
+mod commands;
 mod config;
 mod dated;
 mod display_console;
 mod display_file;
 mod display_tui;
+mod history;
 mod logging;
 mod tasks;
 mod time;
 mod words;
 
+use std::path::PathBuf;
+use std::process::ExitCode;
+// dependencies
+use chrono::NaiveDate;
+use clap::Parser;
 // internal
+use crate::cli::{Cli, Command, LogLevelArg};
 use crate::tasks::data::TaskData;
+use crate::tasks::task::meta;
+use crate::tasks::types;
 
-const OPTION_DEBUG: &str = "--debug";
-const OPTION_VERSION_SHORT: &str = "-v";
-const OPTION_VERSION_LONG: &str = "--version";
-const OPTION_DATED: &str = "--dated";
-const OPTION_TODAY: &str = "--today";
-const OPTION_TUI: &str = "--tui";
-
-enum Intent {
-    FileUpdateOnly,
-    PrintDated,
-    PrintToday,
-    RunTUI,
-}
+fn main() -> ExitCode {
+    let cli: Cli = Cli::parse();
+
+    let command: Command = cli.command.unwrap_or(Command::Export { output_dir: None });
+    let output_dir_override: Option<PathBuf> = match &command {
+        Command::Export { output_dir } => output_dir.clone(),
+        _ => None,
+    };
 
-fn main() {
-    if std::env::args().nth(2).is_some() {
-        println!("Too many arguments!");
-        return;
+    set_log_level(&cli.log_level, cli.debug, &command);
+
+    // a simulated date must not overwrite the real output, unless asked for explicitly
+    let to_update_file: bool = cli.date.is_none() || matches!(command, Command::Export { .. });
+    if let Some(date) = cli.date {
+        time::set_today(date);
     }
 
-    let mut intent: Intent = Intent::FileUpdateOnly;
-
-    if let Some(argument) = std::env::args().nth(1) {
-        if argument == OPTION_DEBUG {
-            logging::set_debug();
-        } else if argument == OPTION_VERSION_SHORT || argument == OPTION_VERSION_LONG {
-            print_version();
-            return;
-        } else if argument == OPTION_DATED {
-            logging::set_warning();
-            intent = Intent::PrintDated;
-        } else if argument == OPTION_TODAY {
-            logging::set_warning();
-            intent = Intent::PrintToday;
-        } else if argument == OPTION_TUI {
-            intent = Intent::RunTUI;
-        } else {
-            println!("Unrecognized option: {argument}");
-            return;
-        }
+    types::set_strict(cli.strict);
+
+    // no data dir needed
+    if let Command::Schema { output_dir } = &command {
+        return exit_code(commands::schema::run(output_dir));
     }
 
-    let (data_dir_todo, data_dir_todo_output, _) = config::load_data_dirs();
+    let config_dir: PathBuf = config::load_dir();
+    let data_dir_todo: PathBuf = match config::load_data_dir_todo(&config_dir, cli.data_dir) {
+        None => return exit_code(false),
+        Some(data_dir) => data_dir,
+    };
+    // only the commands listing the tasks write the output file
+    let writes_output_file: bool = to_update_file
+        && !matches!(
+            command,
+            Command::Check
+                | Command::Fmt { .. }
+                | Command::Migrate { .. }
+                | Command::History { .. }
+        );
+    let data_dir_todo_output_opt: Option<PathBuf> = match writes_output_file {
+        false => None,
+        true => match config::load_data_dir_todo_output(&config_dir, output_dir_override) {
+            None => return exit_code(false),
+            Some(data_dir) => Some(data_dir),
+        },
+    };
+    meta::set_named_intervals(config::load_named_intervals(&config_dir));
+
+    let is_success: bool = match &command {
+        Command::Add {
+            prefix,
+            title,
+            note,
+            due,
+            time_of_day,
+        } => {
+            let due: String = due
+                .clone()
+                .unwrap_or_else(|| time::today().format("%Y-%m-%d").to_string());
+            commands::add::run(
+                data_dir_todo.as_ref(),
+                prefix,
+                title,
+                note,
+                &due,
+                time_of_day,
+            )
+        }
+        Command::New { kind } => commands::new::run(data_dir_todo.as_ref(), kind),
+        Command::Done { task, date } => {
+            let date: NaiveDate = date.unwrap_or_else(time::today);
+            commands::done::run(data_dir_todo.as_ref(), task, &date)
+        }
+        Command::Archive { older_than } => {
+            commands::archive::run(data_dir_todo.as_ref(), *older_than)
+        }
+        Command::Check => {
+            return exit_code(commands::check::run(data_dir_todo.as_ref()));
+        }
+        Command::Fmt { check } => {
+            return exit_code(commands::fmt::run(data_dir_todo.as_ref(), *check));
+        }
+        Command::Migrate { dry_run } => {
+            return exit_code(commands::migrate::run(data_dir_todo.as_ref(), *dry_run));
+        }
+        Command::History { task, archive } => {
+            return exit_code(commands::history::run(
+                data_dir_todo.as_ref(),
+                task,
+                *archive,
+            ));
+        }
+        Command::Snooze { task, until, clear } => {
+            let date_opt: Option<NaiveDate> = match (until, clear) {
+                (_, true) => None,
+                (None, false) => unreachable!(),
+                (Some(until), false) => match parse_snooze_until(until) {
+                    None => return exit_code(false),
+                    Some(date) => Some(date),
+                },
+            };
+            commands::snooze::run(data_dir_todo.as_ref(), task, date_opt.as_ref())
+        }
+        Command::Schema { .. } => unreachable!(),
+        Command::Export { .. } | Command::Show | Command::Today | Command::Tui => true,
+    };
+    if !is_success {
+        return exit_code(false);
+    }
 
     let task_data: TaskData = TaskData::load(data_dir_todo.as_ref());
-    display_file::dated::print(&task_data, data_dir_todo_output.as_ref());
+    if let Some(data_dir_todo_output) = &data_dir_todo_output_opt {
+        display_file::dated::print(&task_data, data_dir_todo_output);
+    }
+
+    match command {
+        Command::Show => display_console::dated::print(&task_data),
+        Command::Today => display_console::dated::print_today(&task_data),
+        Command::Tui => {
+            let task_data: TaskData =
+                display_tui::run(data_dir_todo.as_ref(), task_data).expect("Error running TUI");
+            // completion may have been toggled in the TUI
+            if let Some(data_dir_todo_output) = &data_dir_todo_output_opt {
+                display_file::dated::print(&task_data, data_dir_todo_output);
+            }
+        }
+        _ => {}
+    }
 
-    match intent {
-        Intent::FileUpdateOnly => {}
-        Intent::PrintDated => display_console::dated::print(&task_data),
-        Intent::PrintToday => display_console::dated::print_today(&task_data),
-        Intent::RunTUI => display_tui::run(&task_data).expect("Error running TUI"),
+    return exit_code(true);
+}
+
+fn set_log_level(log_level_opt: &Option<LogLevelArg>, debug: bool, command: &Command) {
+    let log_level: LogLevelArg = match (log_level_opt, debug) {
+        (Some(log_level), _) => log_level.clone(),
+        (None, true) => LogLevelArg::Debug,
+        (None, false) => match command {
+            // the problems are printed to the console instead
+            Command::Check => LogLevelArg::None,
+            Command::Show
+            | Command::Today
+            | Command::History { .. }
+            | Command::Archive { .. }
+            | Command::Fmt { .. }
+            | Command::Migrate { .. } => LogLevelArg::Warning,
+            _ => LogLevelArg::Info,
+        },
+    };
+    match log_level {
+        LogLevelArg::None => logging::set_none(),
+        LogLevelArg::Error => logging::set_error(),
+        LogLevelArg::Warning => logging::set_warning(),
+        LogLevelArg::Info => logging::set_info(),
+        LogLevelArg::Debug => logging::set_debug(),
+    }
+}
+
+fn parse_snooze_until(until: &str) -> Option<NaiveDate> {
+    // either a number of days from today or a date
+    if until.is_empty() || !until.chars().all(|character| character.is_ascii_digit()) {
+        return time::parsing::date_opt_from_str(until, "snooze command", until);
+    }
+    let date_opt: Option<NaiveDate> = until
+        .parse::<u32>()
+        .ok()
+        .and_then(|day_count| time::add_days(&time::today(), day_count));
+    if date_opt.is_none() {
+        logging::error(format!(
+            "Bad day count '{}' in snooze command, too many days",
+            until
+        ));
     }
+    return date_opt;
 }
 
-fn print_version() {
-    let name: &str = env!("CARGO_PKG_NAME");
-    let version: &str = env!("CARGO_PKG_VERSION");
-    println!("{name} {version}");
+fn exit_code(is_success: bool) -> ExitCode {
+    return match is_success {
+        true => ExitCode::SUCCESS,
+        false => ExitCode::from(cli::EXIT_FAILURE),
+    };
 }
@@ -3,6 +3,7 @@
 // file, You can obtain one at http://mozilla.org/MPL/2.0/.
 
 pub(crate) mod dates;
+pub(crate) mod problem;
 pub(crate) mod sections;
 
 use std::fs::{self, DirEntry};
@@ -11,6 +12,7 @@ use std::path::{Display, Path, PathBuf};
 use chrono::NaiveDate;
 // internal
 use crate::tasks::data::dates::TaskDates;
+use crate::tasks::data::problem::TaskProblem;
 use crate::tasks::data::sections::TaskSections;
 use crate::tasks::task::contents::TaskVisibility;
 use crate::tasks::task::Task;
@@ -22,13 +24,18 @@ use crate::{logging, time};
 pub(crate) struct TaskData {
     pub(crate) dates: TaskDates,
     pub(crate) sections: TaskSections,
+    pub(crate) problems: Vec<TaskProblem>,
 }
 
 impl TaskData {
     pub(crate) fn load(data_dir_todo: &Path) -> Self {
         let dates: TaskDates = TaskDates::create();
         let sections: TaskSections = Default::default();
-        let mut data: TaskData = TaskData { dates, sections };
+        let mut data: TaskData = TaskData {
+            dates,
+            sections,
+            problems: Default::default(),
+        };
         {
             let dir_path: PathBuf = data_dir_todo.join(type_marked_day::DIR_NAME);
             data.load_subdir(&dir_path, &type_marked_day::load);
@@ -81,17 +88,32 @@ impl TaskData {
 
 pub(crate) trait TaskAddable {
     fn add_task(&mut self, task_date: NaiveDate, task: Task);
+    fn add_problem(&mut self, problem: TaskProblem);
     fn year_current(&self) -> i32;
     fn year_next(&self) -> i32;
     fn date_today(&self) -> NaiveDate;
     fn date_tomorrow(&self) -> NaiveDate;
+    fn date_last_dated(&self) -> NaiveDate;
 }
 
 impl TaskAddable for TaskData {
-    fn add_task(&mut self, task_date: NaiveDate, task: Task) {
+    fn add_task(&mut self, task_date: NaiveDate, mut task: Task) {
         let task_sections: &mut TaskSections = &mut self.sections;
         let task_dates: &TaskDates = &self.dates;
 
+        let task_date: NaiveDate = match task.meta.snoozed_until {
+            Some(snoozed_until) if snoozed_until > task_date => {
+                task.meta.overdue = snoozed_until < task_dates.today;
+                task.meta.display_options.overdue_mark = snoozed_until == task_dates.today;
+                snoozed_until
+            }
+            _ => {
+                // a snooze that ended before the task date has no effect anymore
+                task.meta.snoozed_until = None;
+                task_date
+            }
+        };
+
         match task.contents.visibility {
             TaskVisibility::Visible => {}
             TaskVisibility::Inactive => {
@@ -121,6 +143,11 @@ impl TaskAddable for TaskData {
         }
     }
 
+    fn add_problem(&mut self, problem: TaskProblem) {
+        logging::error(problem.to_string());
+        self.problems.push(problem);
+    }
+
     fn year_current(&self) -> i32 {
         return self.dates.current_year;
     }
@@ -136,4 +163,8 @@ impl TaskAddable for TaskData {
     fn date_tomorrow(&self) -> NaiveDate {
         return time::increment_by_one_day(&self.dates.today);
     }
+
+    fn date_last_dated(&self) -> NaiveDate {
+        return self.dates.last_dated;
+    }
 }
@@ -0,0 +1,38 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+pub(crate) struct TaskProblem {
+    pub(crate) file_path: PathBuf,
+    pub(crate) json_path: String,
+    pub(crate) title: String,
+    pub(crate) reason: String,
+}
+
+impl TaskProblem {
+    pub(crate) fn new(file_path: &Path, json_path: &str, title: &str, reason: String) -> Self {
+        return TaskProblem {
+            file_path: file_path.to_path_buf(),
+            json_path: json_path.to_string(),
+            title: title.to_string(),
+            reason,
+        };
+    }
+}
+
+impl fmt::Display for TaskProblem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut display: String = self.file_path.display().to_string();
+        if !self.json_path.is_empty() {
+            display = format!("{}#{}", display, self.json_path);
+        }
+        display = format!("{}: {}", display, self.reason);
+        if !self.title.is_empty() {
+            display = format!("{} ({})", display, self.title);
+        }
+        return write!(f, "{}", display);
+    }
+}
@@ -31,6 +31,23 @@ impl Default for TaskSections {
 }
 
 impl TaskSections {
+    // undated lists (today and inactive) are paired with the given date
+    pub(crate) fn iter_all<'a>(
+        &'a self,
+        today: &'a NaiveDate,
+    ) -> impl Iterator<Item = (&'a NaiveDate, &'a Task)> {
+        let dated_lists = self
+            .overdue
+            .iter()
+            .chain(self.rest_of_the_week.iter())
+            .chain(self.dated.iter())
+            .chain(self.later.iter());
+        return dated_lists
+            .flat_map(|(task_date, task_list)| task_list.iter().map(move |task| (task_date, task)))
+            .chain(self.today.iter().map(move |task| (today, task)))
+            .chain(self.inactive.iter().map(move |task| (today, task)));
+    }
+
     pub(crate) fn sort_task_lists(&mut self) {
         for task_list in self.overdue.values_mut() {
             task_list.sort();
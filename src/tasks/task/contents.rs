@@ -9,9 +9,10 @@ pub(crate) struct TaskContents {
     pub(crate) note: String,
     pub(crate) is_done: bool,
     pub(crate) visibility: TaskVisibility,
+    pub(crate) origin_index: Option<usize>,
 }
 
-#[derive(PartialEq)]
+#[derive(PartialEq, Clone, Copy)]
 pub(crate) enum TaskVisibility {
     Visible,
     Inactive,
@@ -2,23 +2,48 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at http://mozilla.org/MPL/2.0/.
 
+use std::collections::BTreeMap;
 use std::fmt;
+use std::sync::RwLock;
 // dependencies
+use chrono::{NaiveDate, NaiveTime, TimeDelta, Timelike};
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 // internal
 use crate::tasks::task::contents::TaskContents;
 
+const MSG_LOCK_FAIL_READ: &str = "Failed to acquire lock to read named intervals.";
+const MSG_LOCK_FAIL_WRITE: &str = "Failed to acquire lock to write named intervals.";
+
+/// Intervals defined in the config by name, e.g. "quarter" for 3 months
+static NAMED_INTERVALS: RwLock<BTreeMap<String, TaskFrequency>> = RwLock::new(BTreeMap::new());
+
+pub(crate) fn set_named_intervals(named_intervals: BTreeMap<String, TaskFrequency>) {
+    let mut lock = NAMED_INTERVALS.write().expect(MSG_LOCK_FAIL_WRITE);
+    *lock = named_intervals;
+}
+
 pub(crate) struct TaskMeta {
     pub(crate) frequency: TaskFrequency,
     pub(crate) time_of_day: TaskTimeOfDay,
+    pub(crate) start_time: Option<NaiveTime>,
+    pub(crate) duration_minutes: Option<u32>,
     pub(crate) overdue: bool,
+    /// A later occurrence, shown ahead of time
+    pub(crate) projected: bool,
+    /// Listed ahead of its due date, in its lead time
+    pub(crate) due_in_days: Option<i64>,
+    /// Reason the task does not recur anymore
+    pub(crate) ended: Option<String>,
     pub(crate) subtasks: Vec<TaskContents>,
+    pub(crate) history: Vec<NaiveDate>,
+    pub(crate) snoozed_until: Option<NaiveDate>,
     pub(crate) display_options: TaskMetaDisplayOptions,
 }
 
 impl fmt::Display for TaskMeta {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let mut display: String = Default::default();
+        let mut display: String = self.format_clock();
 
         if self.time_of_day != TaskTimeOfDay::Any {
             display = format!("{} ({})", display, self.time_of_day);
@@ -32,6 +57,22 @@ impl fmt::Display for TaskMeta {
             display.push_str("(OD) ");
         }
 
+        if self.snoozed_until.is_some() {
+            display.push_str("(SN) ");
+        }
+
+        if self.projected {
+            display.push_str("(PJ) ");
+        }
+
+        if let Some(days) = self.due_in_days {
+            display.push_str(&format!("({}) ", format_due_in_days(days)));
+        }
+
+        if let Some(reason) = &self.ended {
+            display.push_str(&format!("(ended: {}) ", reason));
+        }
+
         if !display.is_empty() {
             display.push_str("- ");
         }
@@ -41,6 +82,36 @@ impl fmt::Display for TaskMeta {
 }
 
 impl TaskMeta {
+    /// Place in the day: the bucket of the time of day, then the clock time
+    pub(crate) fn time_rank(&self) -> (u8, bool, Option<NaiveTime>) {
+        let time_of_day: &TaskTimeOfDay = match &self.start_time {
+            None => &self.time_of_day,
+            Some(start_time) => TaskTimeOfDay::of_time(start_time),
+        };
+        return (
+            time_of_day.rank(),
+            self.start_time.is_none(),
+            self.start_time,
+        );
+    }
+
+    /// Start and end time, the duration alone without a start time
+    fn format_clock(&self) -> String {
+        return match (self.start_time, self.duration_minutes) {
+            (None, None) => "".to_string(),
+            (None, Some(minutes)) => format_duration(minutes),
+            (Some(start_time), None) => start_time.format("%H:%M").to_string(),
+            (Some(start_time), Some(minutes)) => {
+                let end_time: NaiveTime = start_time + TimeDelta::minutes(minutes as i64);
+                format!(
+                    "{}-{}",
+                    start_time.format("%H:%M"),
+                    end_time.format("%H:%M")
+                )
+            }
+        };
+    }
+
     pub(crate) fn format_as_table_row(&self) -> String {
         let overdue_display: &str = if self.overdue { "OD" } else { "  " };
 
@@ -59,23 +130,50 @@ impl TaskMeta {
             frequency_interval_display = format!("{: <7}", text);
         }
 
-        return match self.display_options.overdue_mark {
+        let clock_display: String = format!("{: <11}", self.format_clock());
+
+        let row: String = match self.display_options.overdue_mark {
             true => format!(
-                "|{}|{}{}|{}|",
+                "|{}|{}|{}{}|{}|",
                 self.time_of_day,
+                clock_display,
                 frequency_number_display,
                 frequency_interval_display,
                 overdue_display
             ),
             false => format!(
-                "|{}|{}{}|",
-                self.time_of_day, frequency_number_display, frequency_interval_display
+                "|{}|{}|{}{}|",
+                self.time_of_day,
+                clock_display,
+                frequency_number_display,
+                frequency_interval_display
             ),
         };
+
+        let row: String = match self.snoozed_until {
+            None => row,
+            Some(_) => format!("{}SN|", row),
+        };
+
+        let row: String = match self.projected {
+            false => row,
+            true => format!("{}PJ|", row),
+        };
+
+        let row: String = match self.due_in_days {
+            None => row,
+            Some(days) => format!("{}{}|", row, format_due_in_days(days)),
+        };
+
+        return match &self.ended {
+            None => row,
+            Some(reason) => format!("{}ended: {}|", row, reason),
+        };
     }
 }
 
-#[derive(Serialize, Deserialize)]
+#[derive(Clone, Serialize, Deserialize, JsonSchema)]
+#[schemars(deny_unknown_fields)]
 pub(crate) struct TaskFrequency {
     pub(crate) number: Option<u8>,
     pub(crate) interval: TaskFrequencyInterval,
@@ -90,6 +188,12 @@ impl Default for TaskFrequency {
     }
 }
 
+impl TaskFrequency {
+    pub(crate) fn is_none(&self) -> bool {
+        return self.number.is_none() && self.interval == TaskFrequencyInterval::None;
+    }
+}
+
 impl fmt::Display for TaskFrequency {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         return match &self.number {
@@ -107,7 +211,7 @@ impl PartialEq<Self> for TaskFrequency {
 }
 impl Eq for TaskFrequency {}
 
-#[derive(PartialOrd, Ord, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Clone, PartialOrd, Ord, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
 pub(crate) enum TaskFrequencyInterval {
     Other(String),
     Day,
@@ -138,6 +242,26 @@ impl fmt::Display for TaskFrequencyInterval {
 }
 
 impl TaskFrequencyInterval {
+    pub(crate) fn is_built_in(&self) -> bool {
+        return !matches!(
+            self,
+            TaskFrequencyInterval::Other(_) | TaskFrequencyInterval::None
+        );
+    }
+
+    /// Built-in interval and its count, a named interval is looked up
+    pub(crate) fn resolve(&self) -> Option<(TaskFrequencyInterval, u32)> {
+        return match self {
+            TaskFrequencyInterval::Other(name) => {
+                let lock = NAMED_INTERVALS.read().expect(MSG_LOCK_FAIL_READ);
+                let named: &TaskFrequency = lock.get(name)?;
+                Some((named.interval.clone(), named.number? as u32))
+            }
+            TaskFrequencyInterval::None => None,
+            interval => Some((interval.clone(), 1)),
+        };
+    }
+
     fn format_frequency_one(&self) -> String {
         return match self {
             TaskFrequencyInterval::Other(text) => text.clone(),
@@ -148,7 +272,7 @@ impl TaskFrequencyInterval {
     }
 }
 
-#[derive(PartialEq, Clone, Serialize, Deserialize)]
+#[derive(PartialEq, Clone, Serialize, Deserialize, JsonSchema)]
 pub(crate) enum TaskTimeOfDay {
     Morning,
     Midday,
@@ -162,6 +286,46 @@ impl Default for TaskTimeOfDay {
     }
 }
 
+impl TaskTimeOfDay {
+    pub(crate) fn is_any(&self) -> bool {
+        return *self == TaskTimeOfDay::Any;
+    }
+
+    /// Bucket of a clock time, the afternoon is left to Any
+    fn of_time(time: &NaiveTime) -> &'static TaskTimeOfDay {
+        return match time.hour() {
+            0..=10 => &TaskTimeOfDay::Morning,
+            11..=13 => &TaskTimeOfDay::Midday,
+            14..=17 => &TaskTimeOfDay::Any,
+            _ => &TaskTimeOfDay::Evening,
+        };
+    }
+
+    fn rank(&self) -> u8 {
+        return match self {
+            TaskTimeOfDay::Morning => 0,
+            TaskTimeOfDay::Midday => 1,
+            TaskTimeOfDay::Any => 2,
+            TaskTimeOfDay::Evening => 3,
+        };
+    }
+}
+
+fn format_due_in_days(days: i64) -> String {
+    return match days {
+        1 => "due in 1 day".to_string(),
+        days => format!("due in {} days", days),
+    };
+}
+
+fn format_duration(minutes: u32) -> String {
+    return match (minutes / 60, minutes % 60) {
+        (0, minutes) => format!("{}m", minutes),
+        (hours, 0) => format!("{}h", hours),
+        (hours, minutes) => format!("{}h{}m", hours, minutes),
+    };
+}
+
 impl fmt::Display for TaskTimeOfDay {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         let time_of_day_mark: &str = match self {
@@ -4,16 +4,28 @@
 
 pub(crate) mod contents;
 pub(crate) mod meta;
+pub(crate) mod origin;
 
 use std::cmp::Ordering;
 use std::fmt;
 // internal
 use crate::tasks::task::contents::TaskContents;
-use crate::tasks::task::meta::{TaskMeta, TaskTimeOfDay};
+use crate::tasks::task::meta::TaskMeta;
+use crate::tasks::task::origin::TaskOrigin;
 
 pub(crate) struct Task {
     pub(crate) meta: TaskMeta,
     pub(crate) contents: TaskContents,
+    pub(crate) origin: TaskOrigin,
+}
+
+impl Task {
+    pub(crate) fn subtask_origin(&self, subtask: &TaskContents) -> TaskOrigin {
+        return match subtask.origin_index {
+            None => self.origin.clone(),
+            Some(index) => self.origin.clone().with_item(index),
+        };
+    }
 }
 
 impl fmt::Display for Task {
@@ -24,14 +36,11 @@ impl fmt::Display for Task {
 
 impl Ord for Task {
     fn cmp(&self, other: &Self) -> Ordering {
-        match (&self.meta.time_of_day, &other.meta.time_of_day) {
-            (TaskTimeOfDay::Morning, _) => return Ordering::Less,
-            (_, TaskTimeOfDay::Morning) => return Ordering::Greater,
-            (TaskTimeOfDay::Evening, _) => return Ordering::Greater,
-            (_, TaskTimeOfDay::Evening) => return Ordering::Less,
-            (TaskTimeOfDay::Midday, TaskTimeOfDay::Any) => return Ordering::Less,
-            (TaskTimeOfDay::Any, TaskTimeOfDay::Midday) => return Ordering::Greater,
-            (_, _) => {}
+        {
+            let ordering: Ordering = self.meta.time_rank().cmp(&other.meta.time_rank());
+            if ordering != Ordering::Equal {
+                return ordering;
+            }
         }
 
         match &self
@@ -85,6 +94,7 @@ impl PartialEq for Task {
     fn eq(&self, other: &Self) -> bool {
         self.meta.frequency == other.meta.frequency
             && self.meta.time_of_day == other.meta.time_of_day
+            && self.meta.start_time == other.meta.start_time
             && self.contents.title == other.contents.title
             && self.contents.note == other.contents.note
             && self.contents.is_done == other.contents.is_done
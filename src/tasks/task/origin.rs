@@ -0,0 +1,94 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use std::fmt;
+use std::path::{Path, PathBuf};
+// internal
+use crate::tasks::types::{type_marked_day, type_progressive, type_recurring, type_simple};
+
+#[derive(Clone)]
+pub(crate) struct TaskOrigin {
+    pub(crate) task_type: TaskType,
+    pub(crate) file_path: PathBuf,
+    pub(crate) day_index: Option<usize>,
+    pub(crate) item_index: Option<usize>,
+    /// Reminder of a marked day item, ahead of the day itself
+    pub(crate) reminder_days_before: Option<u32>,
+}
+
+impl TaskOrigin {
+    pub(crate) fn new(task_type: TaskType, file_path: &Path) -> Self {
+        return TaskOrigin {
+            task_type,
+            file_path: file_path.to_path_buf(),
+            day_index: None,
+            item_index: None,
+            reminder_days_before: None,
+        };
+    }
+
+    pub(crate) fn with_day(mut self, day_index: usize) -> Self {
+        self.day_index = Some(day_index);
+        return self;
+    }
+
+    pub(crate) fn with_item(mut self, item_index: usize) -> Self {
+        self.item_index = Some(item_index);
+        return self;
+    }
+
+    pub(crate) fn with_reminder(mut self, days_before: u32) -> Self {
+        self.reminder_days_before = Some(days_before);
+        return self;
+    }
+
+    pub(crate) fn json_path(&self) -> String {
+        return match (self.day_index, self.item_index) {
+            (None, None) => Default::default(),
+            (Some(day_index), None) => format!("days[{}]", day_index),
+            (None, Some(item_index)) => match self.task_type {
+                TaskType::Recurring => format!("subtasks[{}]", item_index),
+                _ => format!("items[{}]", item_index),
+            },
+            (Some(day_index), Some(item_index)) => {
+                format!("days[{}].items[{}]", day_index, item_index)
+            }
+        };
+    }
+}
+
+impl fmt::Display for TaskOrigin {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let file_name: String = match self.file_path.file_name() {
+            None => self.file_path.display().to_string(),
+            Some(file_name) => file_name.to_string_lossy().to_string(),
+        };
+        let json_path: String = self.json_path();
+        return if json_path.is_empty() {
+            write!(f, "{}/{}", self.task_type, file_name)
+        } else {
+            write!(f, "{}/{}#{}", self.task_type, file_name, json_path)
+        };
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub(crate) enum TaskType {
+    MarkedDay,
+    Progressive,
+    Recurring,
+    Simple,
+}
+
+impl fmt::Display for TaskType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let dir_name: &str = match self {
+            TaskType::MarkedDay => type_marked_day::DIR_NAME,
+            TaskType::Progressive => type_progressive::DIR_NAME,
+            TaskType::Recurring => type_recurring::DIR_NAME,
+            TaskType::Simple => type_simple::DIR_NAME,
+        };
+        return write!(f, "{}", dir_name);
+    }
+}
@@ -7,17 +7,111 @@ pub(crate) mod type_progressive;
 pub(crate) mod type_recurring;
 pub(crate) mod type_simple;
 
-use std::fs::File;
-use std::path::Path;
+use std::fs::{self, File};
+use std::io::BufReader;
+use std::path::{Path, PathBuf};
+use std::sync::RwLock;
 // dependencies
-use serde::Deserialize;
-use serde_json::from_reader;
+use chrono::{Datelike, NaiveDate, NaiveTime, Weekday};
+use schemars::{schema_for, JsonSchema, Schema};
+use serde::{Deserialize, Serialize};
+use serde_json::{to_string_pretty, Map, Value};
 // internal
 use crate::logging;
+use crate::tasks::data::problem::TaskProblem;
 use crate::tasks::data::TaskAddable;
+use crate::tasks::task::origin::{TaskOrigin, TaskType};
+use crate::time;
 
 pub(crate) type FnLoadTaskType = dyn Fn(&Path, &mut dyn TaskAddable);
 
+/// Upgrades the JSON object of a task file by one version
+pub(crate) type FnMigrate = fn(&mut Map<String, Value>) -> Result<(), String>;
+
+/// Data of a task file, with the migrations of its older versions
+pub(crate) trait TaskFile: Serialize + for<'de> Deserialize<'de> + JsonSchema {
+    /// The migration at index N upgrades a file from version N to N + 1,
+    /// so the current version is the number of migrations
+    const MIGRATIONS: &'static [FnMigrate];
+
+    /// Brings the contents to their canonical form, e.g. dates and order
+    fn normalize(&mut self);
+}
+
+/// Operations on the files of a task type, for the commands working on all of them
+pub(crate) struct TaskFileType {
+    pub(crate) dir_name: &'static str,
+    pub(crate) version_current: fn() -> u32,
+    pub(crate) rewrite: fn(&Path) -> bool,
+    pub(crate) format: fn(&Path) -> Option<String>,
+    /// Titled by the dir name
+    pub(crate) json_schema: fn(&str) -> Schema,
+}
+
+pub(crate) const TASK_FILE_TYPES: [TaskFileType; 4] = [
+    TaskFileType {
+        dir_name: type_marked_day::DIR_NAME,
+        version_current: version_current::<type_marked_day::Data>,
+        rewrite: rewrite::<type_marked_day::Data>,
+        format: format::<type_marked_day::Data>,
+        json_schema: json_schema::<type_marked_day::Data>,
+    },
+    TaskFileType {
+        dir_name: type_progressive::DIR_NAME,
+        version_current: version_current::<type_progressive::Data>,
+        rewrite: rewrite::<type_progressive::Data>,
+        format: format::<type_progressive::Data>,
+        json_schema: json_schema::<type_progressive::Data>,
+    },
+    TaskFileType {
+        dir_name: type_recurring::DIR_NAME,
+        version_current: version_current::<type_recurring::Data>,
+        rewrite: rewrite::<type_recurring::Data>,
+        format: format::<type_recurring::Data>,
+        json_schema: json_schema::<type_recurring::Data>,
+    },
+    TaskFileType {
+        dir_name: type_simple::DIR_NAME,
+        version_current: version_current::<type_simple::Data>,
+        rewrite: rewrite::<type_simple::Data>,
+        format: format::<type_simple::Data>,
+        json_schema: json_schema::<type_simple::Data>,
+    },
+];
+
+pub(crate) const VERSION_FIELD: &str = "version";
+
+const MSG_LOCK_FAIL_READ: &str = "Failed to acquire lock to read strict mode.";
+const MSG_LOCK_FAIL_WRITE: &str = "Failed to acquire lock to write strict mode.";
+
+/// Unknown fields are rejected instead of ignored
+static STRICT: RwLock<bool> = RwLock::new(false);
+
+#[derive(Serialize, Deserialize, JsonSchema)]
+pub(crate) enum DataWeekday {
+    Mon,
+    Tue,
+    Wed,
+    Thu,
+    Fri,
+    Sat,
+    Sun,
+}
+
+impl DataWeekday {
+    pub(crate) fn to_chrono_weekday(&self) -> Weekday {
+        return match self {
+            DataWeekday::Mon => Weekday::Mon,
+            DataWeekday::Tue => Weekday::Tue,
+            DataWeekday::Wed => Weekday::Wed,
+            DataWeekday::Thu => Weekday::Thu,
+            DataWeekday::Fri => Weekday::Fri,
+            DataWeekday::Sat => Weekday::Sat,
+            DataWeekday::Sun => Weekday::Sun,
+        };
+    }
+}
+
 pub(crate) fn default_true() -> bool {
     return true;
 }
@@ -30,32 +124,370 @@ pub(crate) fn default_zero_i32() -> i32 {
     return 0;
 }
 
-pub(crate) fn default_vec<T>() -> Vec<T> {
-    return Default::default();
+pub(crate) fn is_true(value: &bool) -> bool {
+    return *value;
 }
 
-pub(crate) fn load<Data: for<'de> Deserialize<'de>>(file_path: &Path) -> Option<Data> {
-    let file = match File::open(file_path) {
-        Err(why) => {
+pub(crate) fn is_false(value: &bool) -> bool {
+    return !*value;
+}
+
+pub(crate) fn is_zero_i32(value: &i32) -> bool {
+    return *value == 0;
+}
+
+pub(crate) fn is_zero_u32(value: &u32) -> bool {
+    return *value == 0;
+}
+
+pub(crate) fn file_stem_of(name: &str) -> String {
+    let stem: String = name
+        .to_lowercase()
+        .chars()
+        .map(|c| if c.is_alphanumeric() { c } else { '-' })
+        .collect();
+    return stem.trim_matches('-').to_string();
+}
+
+pub(crate) fn set_strict(is_strict: bool) {
+    let mut lock = STRICT.write().expect(MSG_LOCK_FAIL_WRITE);
+    *lock = is_strict;
+}
+
+fn is_strict() -> bool {
+    return *STRICT.read().expect(MSG_LOCK_FAIL_READ);
+}
+
+pub(crate) fn version_current<Data: TaskFile>() -> u32 {
+    return Data::MIGRATIONS.len() as u32;
+}
+
+/// Migration of the files written before versioning, the version field is all they miss
+pub(crate) fn migrate_to_versioned(_: &mut Map<String, Value>) -> Result<(), String> {
+    return Ok(());
+}
+
+pub(crate) fn load<Data: TaskFile>(file_path: &Path) -> Option<Data> {
+    return match read(file_path, is_strict()) {
+        Err(problems) => {
+            for problem in problems {
+                logging::error(problem.to_string());
+            }
+            None
+        }
+        Ok(data) => Some(data),
+    };
+}
+
+/// Loads a task file to write it back, unknown fields are rejected as they would be lost
+pub(crate) fn load_to_rewrite<Data: TaskFile>(file_path: &Path) -> Option<Data> {
+    return match read(file_path, true) {
+        Err(problems) => {
+            for problem in problems {
+                logging::error(problem.to_string());
+            }
             logging::error(format!(
-                "Couldn't open todo file '{}' \n{}",
-                file_path.display(),
-                why
+                "Todo file '{}' is left unchanged, it is written back only without unknown fields",
+                file_path.display()
             ));
-            return None;
+            None
+        }
+        Ok(data) => Some(data),
+    };
+}
+
+pub(crate) fn load_or_report<Data: TaskFile>(
+    file_path: &Path,
+    task_data: &mut dyn TaskAddable,
+) -> Option<Data> {
+    return match read(file_path, is_strict()) {
+        Err(problems) => {
+            for problem in problems {
+                task_data.add_problem(problem);
+            }
+            None
+        }
+        Ok(data) => Some(data),
+    };
+}
+
+/// Clock time of a task, an invalid one is reported and left out
+pub(crate) fn start_time_or_report(
+    time_opt: &Option<String>,
+    file_path: &Path,
+    json_path: &str,
+    title: &str,
+    task_data: &mut dyn TaskAddable,
+) -> Option<NaiveTime> {
+    let time_string: &String = time_opt.as_ref()?;
+    return match time::parsing::time_from_str(time_string) {
+        Err(reason) => {
+            task_data.add_problem(TaskProblem::new(file_path, json_path, title, reason));
+            None
+        }
+        Ok(time) => Some(time),
+    };
+}
+
+/// Version of a task file as it is on disk, files before versioning have none
+pub(crate) fn version_on_disk(file_path: &Path) -> Result<u32, TaskProblem> {
+    let value: Value = read_value(file_path)?;
+    return version_of(file_path, &value);
+}
+
+fn read_value(file_path: &Path) -> Result<Value, TaskProblem> {
+    let file = match File::open(file_path) {
+        Err(why) => {
+            let reason: String = format!("Couldn't open todo file: {}", why);
+            return Err(TaskProblem::new(file_path, "", "", reason));
         }
         Ok(file) => file,
     };
 
-    match from_reader(file) {
+    return serde_json::from_reader(BufReader::new(file)).map_err(|why| {
+        let reason: String = format!("Couldn't parse todo file: {}", why);
+        return TaskProblem::new(file_path, "", "", reason);
+    });
+}
+
+fn version_of(file_path: &Path, value: &Value) -> Result<u32, TaskProblem> {
+    return match value.get(VERSION_FIELD) {
+        None => Ok(0),
+        Some(version) => match version.as_u64() {
+            Some(version) if version <= u32::MAX as u64 => Ok(version as u32),
+            _ => Err(TaskProblem::new(
+                file_path,
+                VERSION_FIELD,
+                "",
+                format!("Invalid version: {}", version),
+            )),
+        },
+    };
+}
+
+fn migrate<Data: TaskFile>(file_path: &Path, value: &mut Value) -> Result<(), TaskProblem> {
+    let version_file: u32 = version_of(file_path, value)?;
+    let version_current: u32 = version_current::<Data>();
+    if version_file > version_current {
+        return Err(TaskProblem::new(
+            file_path,
+            VERSION_FIELD,
+            "",
+            format!(
+                "Version {} is newer than the supported version {}",
+                version_file, version_current
+            ),
+        ));
+    }
+
+    let object: &mut Map<String, Value> = match value.as_object_mut() {
+        None => {
+            let reason: String = "Couldn't parse todo file: not a JSON object".to_string();
+            return Err(TaskProblem::new(file_path, "", "", reason));
+        }
+        Some(object) => object,
+    };
+    for version in version_file..version_current {
+        if let Err(reason) = Data::MIGRATIONS[version as usize](object) {
+            return Err(TaskProblem::new(
+                file_path,
+                "",
+                "",
+                format!("Couldn't migrate from version {}: {}", version, reason),
+            ));
+        }
+    }
+    object.insert(VERSION_FIELD.to_string(), Value::from(version_current));
+    return Ok(());
+}
+
+fn read<Data: TaskFile>(file_path: &Path, is_strict: bool) -> Result<Data, Vec<TaskProblem>> {
+    let mut value: Value = read_value(file_path).map_err(|problem| vec![problem])?;
+    // older files are upgraded in memory, 'migrate' rewrites them on disk
+    migrate::<Data>(file_path, &mut value).map_err(|problem| vec![problem])?;
+
+    let mut unknown_fields: Vec<String> = Default::default();
+    let mut on_ignored = |path: serde_ignored::Path| unknown_fields.push(json_path_of(&path));
+    let result =
+        serde_path_to_error::deserialize(serde_ignored::Deserializer::new(value, &mut on_ignored));
+    let data: Data = match result {
+        Err(why) => {
+            // the path of an error at the root is "."
+            let json_path: String = why.path().to_string().trim_start_matches('.').to_string();
+            let reason: String = format!("Couldn't parse todo file: {}", why.inner());
+            return Err(vec![TaskProblem::new(file_path, &json_path, "", reason)]);
+        }
+        Ok(data) => data,
+    };
+
+    if is_strict && !unknown_fields.is_empty() {
+        return Err(unknown_fields
+            .iter()
+            .map(|json_path| {
+                TaskProblem::new(file_path, json_path, "", "Unknown field".to_string())
+            })
+            .collect());
+    }
+    for json_path in unknown_fields {
+        logging::debug(format!(
+            "Ignoring unknown field in todo file '{}#{}'",
+            file_path.display(),
+            json_path
+        ));
+    }
+
+    return Ok(data);
+}
+
+fn json_path_of(path: &serde_ignored::Path) -> String {
+    return match path {
+        serde_ignored::Path::Root => Default::default(),
+        serde_ignored::Path::Seq { parent, index } => {
+            format!("{}[{}]", json_path_of(parent), index)
+        }
+        serde_ignored::Path::Map { parent, key } => match json_path_of(parent).as_str() {
+            "" => key.clone(),
+            parent_path => format!("{}.{}", parent_path, key),
+        },
+        serde_ignored::Path::Some { parent }
+        | serde_ignored::Path::NewtypeStruct { parent }
+        | serde_ignored::Path::NewtypeVariant { parent } => json_path_of(parent),
+    };
+}
+
+pub(crate) fn save<Data: Serialize>(file_path: &Path, data: &Data) -> bool {
+    let text: String = match to_text(file_path, data) {
+        None => return false,
+        Some(text) => text,
+    };
+
+    if let Err(why) = fs::write(file_path, text) {
+        logging::error(format!(
+            "Couldn't write todo file '{}' \n{}",
+            file_path.display(),
+            why
+        ));
+        return false;
+    }
+
+    return true;
+}
+
+/// The canonical text of a task file: pretty-printed in the field order of its data
+fn to_text<Data: Serialize>(file_path: &Path, data: &Data) -> Option<String> {
+    return match to_string_pretty(data) {
         Err(why) => {
             logging::error(format!(
-                "Couldn't parse todo file '{}' \n{}",
+                "Couldn't write todo file '{}' \n{}",
                 file_path.display(),
                 why
             ));
-            return None;
+            None
         }
-        Ok(data) => Some(data),
+        Ok(text) => Some(format!("{}\n", text)),
+    };
+}
+
+/// Writes the file back in the current version
+pub(crate) fn rewrite<Data: TaskFile>(file_path: &Path) -> bool {
+    return match load_to_rewrite::<Data>(file_path) {
+        None => false,
+        Some(data) => save(file_path, &data),
+    };
+}
+
+pub(crate) fn json_schema<Data: TaskFile>(title: &str) -> Schema {
+    let mut schema: Schema = schema_for!(Data);
+    schema.insert("title".to_string(), Value::from(title));
+    return schema;
+}
+
+/// Canonical text of a task file, with normalized contents
+pub(crate) fn format<Data: TaskFile>(file_path: &Path) -> Option<String> {
+    let mut data: Data = load_to_rewrite(file_path)?;
+    data.normalize();
+    return to_text(file_path, &data);
+}
+
+/// Rewrites a valid time as HH:MM, e.g. with the missing leading zero
+pub(crate) fn normalize_time(time_string: &mut String) {
+    if let Ok(time) = time::parsing::time_from_str(time_string) {
+        *time_string = time.format("%H:%M").to_string();
+    }
+}
+
+/// Rewrites a valid date as YYYY-MM-DD, e.g. with the missing leading zeros
+pub(crate) fn normalize_date(date_string: &mut String) {
+    if let Ok(date) = time::parsing::date_from_str(date_string) {
+        *date_string = date.format("%Y-%m-%d").to_string();
     }
 }
+
+/// Files of a task type dir, in a stable order
+pub(crate) fn file_paths_sorted(dir_path: &Path) -> Vec<PathBuf> {
+    let mut file_paths: Vec<PathBuf> = fs::read_dir(dir_path)
+        .expect("Failed to iterate todo subdir.")
+        .map(|entry| entry.expect("Failed to iterate dir entry.").path())
+        .filter(|entry_path| !entry_path.is_dir())
+        .collect();
+    file_paths.sort();
+    return file_paths;
+}
+
+pub(crate) fn toggle_done(origin: &TaskOrigin, task_date: &NaiveDate, today: &NaiveDate) -> bool {
+    let file_path: &Path = origin.file_path.as_path();
+    if let (TaskType::MarkedDay, Some(day_index), Some(item_index), Some(days_before)) = (
+        origin.task_type,
+        origin.day_index,
+        origin.item_index,
+        origin.reminder_days_before,
+    ) {
+        // the reminder is for the marked day in the year of the day, not of the reminder
+        return match time::add_days(task_date, days_before) {
+            None => false,
+            Some(date_marked) => type_marked_day::toggle_reminded(
+                file_path,
+                day_index,
+                item_index,
+                days_before,
+                date_marked.year(),
+            ),
+        };
+    }
+    return match (origin.task_type, origin.day_index, origin.item_index) {
+        (TaskType::MarkedDay, Some(day_index), item_index_opt) => {
+            type_marked_day::toggle_observed(file_path, day_index, item_index_opt, task_date.year())
+        }
+        (TaskType::Progressive, Some(day_index), Some(item_index)) => {
+            type_progressive::toggle_item_done(file_path, day_index, item_index, today)
+        }
+        (TaskType::Recurring, None, None) => type_recurring::mark_done(file_path, today),
+        (TaskType::Recurring, None, Some(subtask_index)) => {
+            type_recurring::toggle_subtask_done(file_path, subtask_index, today)
+        }
+        (TaskType::Simple, None, Some(item_index)) => {
+            type_simple::toggle_item_done(file_path, item_index, today)
+        }
+        (_, _, _) => {
+            logging::error(format!(
+                "Unable to toggle completion of task at '{}'",
+                origin
+            ));
+            false
+        }
+    };
+}
+
+pub(crate) fn set_snoozed_until(origin: &TaskOrigin, date_opt: Option<&NaiveDate>) -> bool {
+    let file_path: &Path = origin.file_path.as_path();
+    return match (origin.task_type, origin.day_index, origin.item_index) {
+        (TaskType::Recurring, None, None) => type_recurring::set_snoozed_until(file_path, date_opt),
+        (TaskType::Simple, None, Some(item_index)) => {
+            type_simple::set_snoozed_until(file_path, item_index, date_opt)
+        }
+        (_, _, _) => {
+            logging::error(format!("Unable to snooze task at '{}'", origin));
+            false
+        }
+    };
+}
@@ -2,161 +2,420 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at http://mozilla.org/MPL/2.0/.
 
+use std::cmp::Reverse;
 use std::path::Path;
 // dependencies
-use chrono::NaiveDate;
+use chrono::{Datelike, Days, NaiveDate, NaiveTime, TimeDelta, Weekday};
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 // internal
+use crate::logging;
+use crate::tasks::data::problem::TaskProblem;
 use crate::tasks::data::TaskAddable;
 use crate::tasks::task::contents::{TaskContents, TaskVisibility};
 use crate::tasks::task::meta::{TaskMeta, TaskMetaDisplayOptions};
+use crate::tasks::task::origin::{TaskOrigin, TaskType};
 use crate::tasks::task::Task;
-use crate::tasks::types;
+use crate::tasks::types::{self, DataWeekday, FnMigrate, TaskFile};
 use crate::time;
 
 pub(crate) const DIR_NAME: &str = "marked-day";
 
-#[derive(serde::Serialize, serde::Deserialize)]
-struct Data {
+/// Days are sorted by their date in this year, a leap year for Feb 29
+const NORMALIZE_YEAR: i32 = 2000;
+
+#[derive(Serialize, Deserialize, JsonSchema)]
+#[schemars(deny_unknown_fields)]
+pub(crate) struct Data {
+    #[serde(default)]
+    version: u32,
     mark_title: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
     description: Option<String>,
+    /// Reminders of every item, in days before the day
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    remind_days_before: Vec<u32>,
+    /// Start of the reminder titles, e.g. "Buy gift for"
+    #[serde(skip_serializing_if = "Option::is_none")]
+    reminder_action: Option<String>,
     days: Vec<DataDay>,
 }
 
-#[derive(serde::Serialize, serde::Deserialize)]
+#[derive(Serialize, Deserialize, JsonSchema)]
+#[schemars(deny_unknown_fields)]
 struct DataDay {
-    month: u32,
-    day: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    month: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    day: Option<u32>,
+    /// Nth weekday of the month instead of the day, e.g. the 2nd Sunday
+    #[serde(skip_serializing_if = "Option::is_none")]
+    weekday: Option<DataWeekdayOfMonth>,
+    /// Days from Easter Sunday instead of the month and day, e.g. -2 for Good Friday
+    #[serde(skip_serializing_if = "Option::is_none")]
+    easter_offset: Option<i32>,
+    /// Date used for February 29 in other years, which fail otherwise
+    #[serde(skip_serializing_if = "Option::is_none")]
+    leap_day_fallback: Option<DataLeapDayFallback>,
     items: Vec<DataItem>,
+
+    /// Clock time as HH:MM
+    #[serde(skip_serializing_if = "Option::is_none")]
+    time: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    duration_minutes: Option<u32>,
+}
+
+#[derive(Serialize, Deserialize, JsonSchema)]
+#[schemars(deny_unknown_fields)]
+struct DataWeekdayOfMonth {
+    weekday: DataWeekday,
+    /// Counted from the end of the month if negative, -1 is the last one
+    nth: i32,
 }
 
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+enum DataLeapDayFallback {
+    #[serde(rename = "feb_28")]
+    Feb28,
+    #[serde(rename = "mar_1")]
+    Mar1,
+    /// No task in the years without February 29
+    Skip,
+}
+
+#[derive(Serialize, Deserialize, JsonSchema)]
+#[schemars(deny_unknown_fields)]
 struct DataItem {
     title: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
     note: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     year: Option<i32>,
     year_last_observed: i32,
+    #[serde(skip_serializing_if = "Option::is_none")]
     hidden: Option<bool>,
+    /// Reminders of the item instead of the ones of the file, none when empty
+    #[serde(skip_serializing_if = "Option::is_none")]
+    remind_days_before: Option<Vec<u32>>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    reminders_observed: Vec<DataReminderObserved>,
+}
+
+#[derive(Serialize, Deserialize, JsonSchema)]
+#[schemars(deny_unknown_fields)]
+struct DataReminderObserved {
+    days_before: u32,
+    year_last_observed: i32,
+}
+
+impl TaskFile for Data {
+    const MIGRATIONS: &'static [FnMigrate] = &[types::migrate_to_versioned];
+
+    fn normalize(&mut self) {
+        self.days
+            .sort_by_key(|day| match date_of_day(day, NORMALIZE_YEAR) {
+                Ok(Some(date)) => (date.month(), date.day()),
+                _ => (day.month.unwrap_or_default(), day.day.unwrap_or_default()),
+            });
+        normalize_days_before(&mut self.remind_days_before);
+        for day in &mut self.days {
+            if let Some(time) = &mut day.time {
+                types::normalize_time(time);
+            }
+            for item in &mut day.items {
+                if let Some(remind_days_before) = &mut item.remind_days_before {
+                    normalize_days_before(remind_days_before);
+                }
+                item.reminders_observed
+                    .sort_by_key(|reminder| Reverse(reminder.days_before));
+            }
+        }
+    }
+}
+
+/// Earliest reminder first
+fn normalize_days_before(days_before: &mut Vec<u32>) {
+    days_before.sort_by_key(|days| Reverse(*days));
+    days_before.dedup();
+}
+
+/// Date of the day in the given year, fixed or by its rule,
+/// none when February 29 is skipped in the year
+fn date_of_day(day: &DataDay, year: i32) -> Result<Option<NaiveDate>, String> {
+    let is_leap_day: bool = day.month == Some(2) && day.day == Some(29) && day.weekday.is_none();
+    if day.leap_day_fallback.is_some() && !is_leap_day {
+        return Err("Use the leap day fallback only for February 29".to_string());
+    }
+
+    if let Some(offset) = day.easter_offset {
+        if day.month.is_some() || day.day.is_some() || day.weekday.is_some() {
+            return Err("Use either an Easter offset or a month".to_string());
+        }
+        return time::easter_sunday(year)
+            .and_then(|easter| easter.checked_add_signed(TimeDelta::days(offset as i64)))
+            .map(Some)
+            .ok_or_else(|| {
+                format!(
+                    "Failed to offset Easter Sunday of {} by {} days",
+                    year, offset
+                )
+            });
+    }
+
+    let month: u32 = match day.month {
+        None => return Err("Missing month".to_string()),
+        Some(month) => month,
+    };
+    return match (&day.weekday, day.day) {
+        (None, None) => Err("Missing day or weekday of the month".to_string()),
+        (Some(_), Some(_)) => Err("Use either a day or a weekday of the month".to_string()),
+        (None, Some(day_of_month)) => {
+            match time::parsing::date_from_ymd(year, month, day_of_month) {
+                Ok(date) => Ok(Some(date)),
+                Err(reason) => match day.leap_day_fallback {
+                    None => Err(reason),
+                    Some(DataLeapDayFallback::Feb28) => {
+                        time::parsing::date_from_ymd(year, 2, 28).map(Some)
+                    }
+                    Some(DataLeapDayFallback::Mar1) => {
+                        time::parsing::date_from_ymd(year, 3, 1).map(Some)
+                    }
+                    Some(DataLeapDayFallback::Skip) => Ok(None),
+                },
+            }
+        }
+        (Some(weekday), None) => {
+            let chrono_weekday: Weekday = weekday.weekday.to_chrono_weekday();
+            time::nth_weekday_of_month(year, month, chrono_weekday, weekday.nth)
+                .map(Some)
+                .ok_or_else(|| {
+                    format!(
+                        "No weekday {} number {} in {}-{}",
+                        chrono_weekday, weekday.nth, year, month
+                    )
+                })
+        }
+    };
 }
 
 pub(crate) fn load(file_path: &Path, task_data: &mut dyn TaskAddable) {
-    let data: Data = match types::load(file_path) {
+    let data: Data = match types::load_or_report(file_path, task_data) {
         None => {
             return;
         }
         Some(data) => data,
     };
 
-    for day in data.days {
-        let date_current_year: NaiveDate = match time::parsing::date_opt_from_ymd(
-            task_data.year_current(),
-            day.month,
-            day.day,
-            "marked day task (current year)",
-            data.mark_title.as_str(),
-        ) {
-            None => {
+    for (day_index, mut day) in data.days.into_iter().enumerate() {
+        let origin: TaskOrigin =
+            TaskOrigin::new(TaskType::MarkedDay, file_path).with_day(day_index);
+
+        let json_path: String = origin.json_path();
+
+        let date_current_year_opt: Option<NaiveDate> =
+            match date_of_day(&day, task_data.year_current()) {
+                Err(reason) => {
+                    task_data.add_problem(TaskProblem::new(
+                        file_path,
+                        &json_path,
+                        &data.mark_title,
+                        format!("{} in the current year", reason),
+                    ));
+                    continue;
+                }
+                Ok(date) => date,
+            };
+
+        let date_next_year_opt: Option<NaiveDate> = match date_of_day(&day, task_data.year_next()) {
+            Err(reason) => {
+                task_data.add_problem(TaskProblem::new(
+                    file_path,
+                    &json_path,
+                    &data.mark_title,
+                    format!("{} in the next year", reason),
+                ));
                 continue;
             }
-            Some(date) => date,
+            Ok(date) => date,
         };
 
-        let date_next_year = match time::parsing::date_opt_from_ymd(
-            task_data.year_next(),
-            day.month,
-            day.day,
-            "marked day task (next year)",
-            data.mark_title.as_str(),
-        ) {
-            None => {
-                continue;
-            }
-            Some(date) => date,
+        let clock: TaskClock = TaskClock {
+            start_time: types::start_time_or_report(
+                &day.time,
+                file_path,
+                &format!("{}.time", json_path),
+                &data.mark_title,
+                task_data,
+            ),
+            duration_minutes: day.duration_minutes,
         };
 
+        let today: NaiveDate = task_data.date_today();
+
         let mut subtasks_current_year: Vec<TaskContents> = Default::default();
         let mut subtasks_next_year: Vec<TaskContents> = Default::default();
 
-        for item in day.items {
+        let items: Vec<DataItem> = std::mem::take(&mut day.items);
+        for (item_index, item) in items.into_iter().enumerate() {
             if item.hidden == Some(true) {
                 continue;
             }
 
             let subtask_note: String = item.note.unwrap_or_else(Default::default);
 
-            let date_last_observed = match time::parsing::date_opt_from_ymd(
-                item.year_last_observed,
-                day.month,
-                day.day,
-                "marked day task (last observed year)",
-                format!("{}) ({}", item.title, data.mark_title).as_str(),
-            ) {
-                None => {
-                    continue;
-                }
-                Some(date) => date,
-            };
+            let date_last_observed_opt: Option<NaiveDate> =
+                match date_of_day(&day, item.year_last_observed) {
+                    Err(reason) => {
+                        task_data.add_problem(TaskProblem::new(
+                            file_path,
+                            &format!("{}.items[{}].year_last_observed", json_path, item_index),
+                            &format!("{}) ({}", item.title, data.mark_title),
+                            reason,
+                        ));
+                        continue;
+                    }
+                    Ok(date) => date,
+                };
 
-            let is_done_for_current_year: bool = date_last_observed >= date_current_year;
+            let is_done_for_current_year: bool =
+                match (date_current_year_opt, date_last_observed_opt) {
+                    // skipped this year, nothing to observe
+                    (None, _) => true,
+                    (Some(date_current_year), Some(date_last_observed)) => {
+                        date_last_observed >= date_current_year
+                    }
+                    (Some(_), None) => item.year_last_observed >= task_data.year_current(),
+                };
 
             let subtask_current_year: TaskContents = TaskContents {
                 title: subtask_title(&item.title, item.year, task_data.year_current()),
                 note: subtask_note.clone(),
                 is_done: is_done_for_current_year,
                 visibility: TaskVisibility::Visible,
+                origin_index: Some(item_index),
             };
 
             subtasks_current_year.push(subtask_current_year);
 
+            // reminders are for the next day not observed yet, until the day has passed
+            let date_reminded_opt: Option<NaiveDate> = if is_done_for_current_year {
+                date_next_year_opt
+            } else {
+                date_current_year_opt.filter(|date_current_year| *date_current_year >= today)
+            };
+            if let Some(date_reminded) = date_reminded_opt {
+                let remind_days_before: &Vec<u32> = item
+                    .remind_days_before
+                    .as_ref()
+                    .unwrap_or(&data.remind_days_before);
+                for days_before in remind_days_before {
+                    let is_observed: bool = item.reminders_observed.iter().any(|reminder| {
+                        reminder.days_before == *days_before
+                            && reminder.year_last_observed >= date_reminded.year()
+                    });
+                    if is_observed {
+                        continue;
+                    }
+                    let date_reminder: NaiveDate =
+                        match date_reminded.checked_sub_days(Days::new(*days_before as u64)) {
+                            None => continue,
+                            Some(date) => date,
+                        };
+                    let task_reminder: Task = create_reminder_task(
+                        reminder_title(
+                            &data.reminder_action,
+                            &item.title,
+                            &data.mark_title,
+                            *days_before,
+                        ),
+                        subtask_note.clone(),
+                        date_reminder < today,
+                        date_reminder == today,
+                        origin
+                            .clone()
+                            .with_item(item_index)
+                            .with_reminder(*days_before),
+                    );
+                    task_data.add_task(date_reminder, task_reminder);
+                }
+            }
+
             if is_done_for_current_year {
                 let subtask_next_year: TaskContents = TaskContents {
                     title: subtask_title(&item.title, item.year, task_data.year_next()),
                     note: subtask_note.clone(),
                     is_done: false,
                     visibility: TaskVisibility::Visible,
+                    origin_index: Some(item_index),
                 };
 
                 subtasks_next_year.push(subtask_next_year);
             }
         }
 
-        let today: NaiveDate = task_data.date_today();
+        let has_open_current_year: bool =
+            !subtasks_current_year.iter().all(|subtask| subtask.is_done)
+                && !subtasks_current_year.is_empty();
 
-        if !subtasks_current_year
-            .iter()
-            .map(|subtask| subtask.is_done)
-            .all(|is_done| is_done)
-            && !subtasks_current_year.is_empty()
-        {
+        if let (Some(date_current_year), true) = (date_current_year_opt, has_open_current_year) {
             let overdue: bool = date_current_year < today;
             let is_today: bool = date_current_year == today;
-            let task_current_year: Task =
-                create_task(subtasks_current_year, overdue, is_today, &data.mark_title);
+            let task_current_year: Task = create_task(
+                subtasks_current_year,
+                &clock,
+                overdue,
+                is_today,
+                &data.mark_title,
+                origin.clone(),
+            );
             task_data.add_task(date_current_year, task_current_year);
         }
 
-        if !subtasks_next_year.is_empty() {
+        if let (Some(date_next_year), false) = (date_next_year_opt, subtasks_next_year.is_empty()) {
             let overdue: bool = date_next_year < today;
             let is_today: bool = date_next_year == today;
-            let task_next_year: Task =
-                create_task(subtasks_next_year, overdue, is_today, &data.mark_title);
+            let task_next_year: Task = create_task(
+                subtasks_next_year,
+                &clock,
+                overdue,
+                is_today,
+                &data.mark_title,
+                origin.clone(),
+            );
             task_data.add_task(date_next_year, task_next_year);
         }
     }
 }
 
+/// Clock time of the day, the same in every year
+struct TaskClock {
+    start_time: Option<NaiveTime>,
+    duration_minutes: Option<u32>,
+}
+
 fn create_task(
     subtasks: Vec<TaskContents>,
+    clock: &TaskClock,
     overdue: bool,
     is_today: bool,
     mark_title: &str,
+    origin: TaskOrigin,
 ) -> Task {
     return Task {
         meta: TaskMeta {
             frequency: Default::default(),
             time_of_day: Default::default(),
+            start_time: clock.start_time,
+            duration_minutes: clock.duration_minutes,
             overdue,
+            projected: false,
+            due_in_days: None,
+            ended: None,
             subtasks,
+            history: Default::default(),
+            snoozed_until: None,
             display_options: TaskMetaDisplayOptions {
                 overdue_mark: is_today,
             },
@@ -166,10 +425,64 @@ fn create_task(
             note: Default::default(),
             is_done: false,
             visibility: TaskVisibility::Visible,
+            origin_index: None,
+        },
+        origin,
+    };
+}
+
+fn create_reminder_task(
+    title: String,
+    note: String,
+    overdue: bool,
+    is_today: bool,
+    origin: TaskOrigin,
+) -> Task {
+    return Task {
+        meta: TaskMeta {
+            frequency: Default::default(),
+            time_of_day: Default::default(),
+            start_time: None,
+            duration_minutes: None,
+            overdue,
+            projected: false,
+            due_in_days: None,
+            ended: None,
+            subtasks: Default::default(),
+            history: Default::default(),
+            snoozed_until: None,
+            display_options: TaskMetaDisplayOptions {
+                overdue_mark: is_today,
+            },
+        },
+        contents: TaskContents {
+            title,
+            note,
+            is_done: false,
+            visibility: TaskVisibility::Visible,
+            origin_index: None,
         },
+        origin,
     };
 }
 
+fn reminder_title(
+    action_opt: &Option<String>,
+    item_title: &String,
+    mark_title: &str,
+    days_before: u32,
+) -> String {
+    let subject: String = match action_opt {
+        None => item_title.clone(),
+        Some(action) => format!("{} {}", action, item_title),
+    };
+    let days_plural: &str = if days_before == 1 { "" } else { "s" };
+    return format!(
+        "{} – {} in {} day{}",
+        subject, mark_title, days_before, days_plural
+    );
+}
+
 fn subtask_title(item_title: &String, origin_year_opt: Option<i32>, task_year: i32) -> String {
     return match origin_year_opt {
         None => item_title.clone(),
@@ -184,3 +497,108 @@ fn subtask_title(item_title: &String, origin_year_opt: Option<i32>, task_year: i
         }
     };
 }
+
+pub(crate) fn toggle_observed(
+    file_path: &Path,
+    day_index: usize,
+    item_index_opt: Option<usize>,
+    task_year: i32,
+) -> bool {
+    let mut data: Data = match types::load_to_rewrite(file_path) {
+        None => {
+            return false;
+        }
+        Some(data) => data,
+    };
+
+    let day: &mut DataDay = match data.days.get_mut(day_index) {
+        None => {
+            logging::error(format!(
+                "No day with index {} in marked day task ({})",
+                day_index, data.mark_title
+            ));
+            return false;
+        }
+        Some(day) => day,
+    };
+
+    match item_index_opt {
+        None => {
+            // whole day: observe every visible item for the year of the task
+            for item in day.items.iter_mut() {
+                if item.hidden != Some(true) && item.year_last_observed < task_year {
+                    item.year_last_observed = task_year;
+                }
+            }
+        }
+        Some(item_index) => {
+            let item: &mut DataItem = match day.items.get_mut(item_index) {
+                None => {
+                    logging::error(format!(
+                        "No item with index {} in marked day task ({})",
+                        item_index, data.mark_title
+                    ));
+                    return false;
+                }
+                Some(item) => item,
+            };
+            if item.year_last_observed >= task_year {
+                item.year_last_observed = task_year - 1;
+            } else {
+                item.year_last_observed = task_year;
+            }
+        }
+    }
+
+    return types::save(file_path, &data);
+}
+
+pub(crate) fn toggle_reminded(
+    file_path: &Path,
+    day_index: usize,
+    item_index: usize,
+    days_before: u32,
+    task_year: i32,
+) -> bool {
+    let mut data: Data = match types::load_to_rewrite(file_path) {
+        None => {
+            return false;
+        }
+        Some(data) => data,
+    };
+
+    let item: &mut DataItem = match data
+        .days
+        .get_mut(day_index)
+        .and_then(|day| day.items.get_mut(item_index))
+    {
+        None => {
+            logging::error(format!(
+                "No item with index {} of day with index {} in marked day task ({})",
+                item_index, day_index, data.mark_title
+            ));
+            return false;
+        }
+        Some(item) => item,
+    };
+
+    match item
+        .reminders_observed
+        .iter_mut()
+        .find(|reminder| reminder.days_before == days_before)
+    {
+        None => item.reminders_observed.push(DataReminderObserved {
+            days_before,
+            year_last_observed: task_year,
+        }),
+        Some(reminder) => {
+            if reminder.year_last_observed >= task_year {
+                reminder.year_last_observed = task_year - 1;
+            } else {
+                reminder.year_last_observed = task_year;
+            }
+        }
+    }
+
+    return types::save(file_path, &data);
+}
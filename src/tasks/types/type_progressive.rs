@@ -4,44 +4,77 @@
 
 use std::path::Path;
 // dependencies
-use chrono::NaiveDate;
+use chrono::{NaiveDate, NaiveTime};
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 // internal
 use crate::logging;
+use crate::tasks::data::problem::TaskProblem;
 use crate::tasks::data::TaskAddable;
 use crate::tasks::task::contents::{TaskContents, TaskVisibility};
 use crate::tasks::task::meta::{
     TaskFrequency, TaskFrequencyInterval, TaskMeta, TaskMetaDisplayOptions, TaskTimeOfDay,
 };
+use crate::tasks::task::origin::{TaskOrigin, TaskType};
 use crate::tasks::task::Task;
-use crate::tasks::types;
+use crate::tasks::types::{self, FnMigrate, TaskFile};
 use crate::time;
 
 pub(crate) const DIR_NAME: &str = "progressive";
 
-#[derive(Serialize, Deserialize)]
-struct Data {
+#[derive(Serialize, Deserialize, JsonSchema)]
+#[schemars(deny_unknown_fields)]
+pub(crate) struct Data {
+    #[serde(default)]
+    version: u32,
     title: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
     description: Option<String>,
     days: Vec<DataDay>,
 }
 
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, JsonSchema)]
+#[schemars(deny_unknown_fields)]
 struct DataDay {
     title: String,
     items: Vec<DataItem>,
 }
 
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, JsonSchema)]
+#[schemars(deny_unknown_fields)]
 struct DataItem {
     done: String,
 
-    #[serde(default = "TaskTimeOfDay::default")]
+    #[serde(
+        default = "TaskTimeOfDay::default",
+        skip_serializing_if = "TaskTimeOfDay::is_any"
+    )]
     time_of_day: TaskTimeOfDay,
+
+    /// Clock time as HH:MM
+    #[serde(skip_serializing_if = "Option::is_none")]
+    time: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    duration_minutes: Option<u32>,
+}
+
+impl TaskFile for Data {
+    const MIGRATIONS: &'static [FnMigrate] = &[types::migrate_to_versioned];
+
+    fn normalize(&mut self) {
+        for day in &mut self.days {
+            for item in &mut day.items {
+                types::normalize_date(&mut item.done);
+                if let Some(time) = &mut item.time {
+                    types::normalize_time(time);
+                }
+            }
+        }
+    }
 }
 
 pub(crate) fn load_one(file_path: &Path, task_data: &mut dyn TaskAddable) {
-    let data: Data = match types::load(file_path) {
+    let data: Data = match types::load_or_report(file_path, task_data) {
         None => {
             return;
         }
@@ -49,74 +82,109 @@ pub(crate) fn load_one(file_path: &Path, task_data: &mut dyn TaskAddable) {
     };
 
     if data.days.is_empty() {
-        logging::error(format!("No days in progressive task  ({})", data.title));
+        task_data.add_problem(TaskProblem::new(
+            file_path,
+            "days",
+            &data.title,
+            "No days in progressive task".to_string(),
+        ));
         return;
     }
 
-    let mut previous_day_last_date_string_opt: Option<&String> = None;
-    let mut current_day_opt: Option<&DataDay> = None;
-
-    for day in &data.days {
+    for (day_index, day) in data.days.iter().enumerate() {
         if day.items.is_empty() {
-            logging::error(format!(
-                "No items in progressive task day: '{}' ({})",
-                day.title, data.title
+            task_data.add_problem(TaskProblem::new(
+                file_path,
+                &format!("days[{}].items", day_index),
+                &data.title,
+                format!("No items in progressive task day: '{}'", day.title),
             ));
+        }
+    }
+
+    let mut previous_day_last_date_opt: Option<(String, &String)> = None;
+    let mut current_day_opt: Option<(usize, &DataDay)> = None;
+
+    for (day_index, day) in data.days.iter().enumerate() {
+        if day.items.is_empty() {
+            // reported above, no later day can be reached
             return;
         }
 
-        if day
-            .items
-            .iter()
-            .map(|item| item.done.is_empty())
-            .any(|is_not_done| is_not_done)
-        {
-            current_day_opt = Some(day);
+        if day.items.iter().any(|item| item.done.is_empty()) {
+            current_day_opt = Some((day_index, day));
             break;
         } else {
             match day.items.last() {
                 None => unreachable!(),
                 Some(item) => {
-                    previous_day_last_date_string_opt = Some(&item.done);
+                    let json_path: String =
+                        format!("days[{}].items[{}].done", day_index, day.items.len() - 1);
+                    previous_day_last_date_opt = Some((json_path, &item.done));
                 }
             }
         }
     }
 
-    let mut last_date_opt: Option<NaiveDate> = match previous_day_last_date_string_opt {
+    let mut last_date_opt: Option<NaiveDate> = match previous_day_last_date_opt {
         None => None,
-        Some(last_date_string) => time::parsing::date_opt_from_str(
-            last_date_string,
-            "progressive task",
-            data.title.as_str(),
-        ),
+        Some((json_path, last_date_string)) => {
+            match time::parsing::date_from_str(last_date_string) {
+                Err(reason) => {
+                    task_data.add_problem(TaskProblem::new(
+                        file_path,
+                        &json_path,
+                        &data.title,
+                        reason,
+                    ));
+                    None
+                }
+                Ok(date) => Some(date),
+            }
+        }
     };
     let mut all_done_for_current_day: bool = true; // default, changed below
-    let current_day: &DataDay = match current_day_opt {
+    let (current_day_index, current_day): (usize, &DataDay) = match current_day_opt {
         None => {
             // no items with empty done: all items done
             return;
         }
-        Some(day) => {
-            if let Some(last_date) = search_for_last_date(&day.items, &data.title) {
+        Some((day_index, day)) => {
+            let json_path: String = format!("days[{}]", day_index);
+            let search_result: Result<Option<NaiveDate>, TaskProblem> =
+                search_for_last_date(&day.items, file_path, &json_path, &data.title);
+            let last_date_search: Option<NaiveDate> = match search_result {
+                Err(problem) => {
+                    task_data.add_problem(problem);
+                    None
+                }
+                Ok(last_date_search) => last_date_search,
+            };
+            if let Some(last_date) = last_date_search {
                 last_date_opt = Some(last_date);
                 all_done_for_current_day = false;
             }
-            day
+            (day_index, day)
         }
     };
 
     let mut task_date: NaiveDate = task_data.date_today();
     match last_date_opt {
-        None => {}
-        Some(last_date) => {
-            if last_date == task_date && all_done_for_current_day {
-                task_date = task_data.date_tomorrow();
-            }
+        Some(last_date) if last_date == task_date && all_done_for_current_day => {
+            task_date = task_data.date_tomorrow();
         }
+        _ => {}
     }
 
-    for item in &current_day.items {
+    for (item_index, item) in current_day.items.iter().enumerate() {
+        let start_time: Option<NaiveTime> = types::start_time_or_report(
+            &item.time,
+            file_path,
+            &format!("days[{}].items[{}].time", current_day_index, item_index),
+            &data.title,
+            task_data,
+        );
+
         let task: Task = Task {
             meta: TaskMeta {
                 frequency: TaskFrequency {
@@ -124,8 +192,15 @@ pub(crate) fn load_one(file_path: &Path, task_data: &mut dyn TaskAddable) {
                     interval: TaskFrequencyInterval::Other("(PR)".to_string()),
                 },
                 time_of_day: item.time_of_day.clone(),
+                start_time,
+                duration_minutes: item.duration_minutes,
                 overdue: false,
+                projected: false,
+                due_in_days: None,
+                ended: None,
                 subtasks: Default::default(),
+                history: Default::default(),
+                snoozed_until: None,
                 display_options: TaskMetaDisplayOptions {
                     overdue_mark: task_date == task_data.date_today(),
                 },
@@ -135,24 +210,89 @@ pub(crate) fn load_one(file_path: &Path, task_data: &mut dyn TaskAddable) {
                 note: current_day.title.clone(),
                 is_done: !item.done.is_empty(),
                 visibility: TaskVisibility::Visible,
+                origin_index: None,
             },
+            origin: TaskOrigin::new(TaskType::Progressive, file_path)
+                .with_day(current_day_index)
+                .with_item(item_index),
         };
 
         task_data.add_task(task_date, task);
     }
 }
 
-fn search_for_last_date(items: &Vec<DataItem>, note_item: &str) -> Option<NaiveDate> {
-    let mut last_date_string_search: Option<&String> = None;
-    for item in items {
+fn search_for_last_date(
+    items: &[DataItem],
+    file_path: &Path,
+    day_json_path: &str,
+    title: &str,
+) -> Result<Option<NaiveDate>, TaskProblem> {
+    let mut last_date_string_search: Option<(usize, &String)> = None;
+    for (item_index, item) in items.iter().enumerate() {
         if item.done.is_empty() {
             break;
         } else {
-            last_date_string_search = Some(&item.done);
+            last_date_string_search = Some((item_index, &item.done));
         }
     }
     return match last_date_string_search {
-        None => None,
-        Some(text) => time::parsing::date_opt_from_str(text, "progressive task", note_item),
+        None => Ok(None),
+        Some((item_index, text)) => match time::parsing::date_from_str(text) {
+            Err(reason) => Err(TaskProblem::new(
+                file_path,
+                &format!("{}.items[{}].done", day_json_path, item_index),
+                title,
+                reason,
+            )),
+            Ok(date) => Ok(Some(date)),
+        },
     };
 }
+
+pub(crate) fn toggle_item_done(
+    file_path: &Path,
+    day_index: usize,
+    item_index: usize,
+    date: &NaiveDate,
+) -> bool {
+    let mut data: Data = match types::load_to_rewrite(file_path) {
+        None => {
+            return false;
+        }
+        Some(data) => data,
+    };
+
+    let day: &mut DataDay = match data.days.get_mut(day_index) {
+        None => {
+            logging::error(format!(
+                "No day with index {} in progressive task ({})",
+                day_index, data.title
+            ));
+            return false;
+        }
+        Some(day) => day,
+    };
+
+    let item_is_done: bool = match day.items.get(item_index) {
+        None => {
+            logging::error(format!(
+                "No item with index {} in progressive task day: '{}' ({})",
+                item_index, day.title, data.title
+            ));
+            return false;
+        }
+        Some(item) => !item.done.is_empty(),
+    };
+
+    if item_is_done {
+        day.items[item_index].done.clear();
+    } else {
+        // progress is always recorded in the next empty item
+        match day.items.iter_mut().find(|item| item.done.is_empty()) {
+            None => unreachable!(),
+            Some(item) => item.done = date.format("%Y-%m-%d").to_string(),
+        }
+    }
+
+    return types::save(file_path, &data);
+}
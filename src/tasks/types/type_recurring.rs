@@ -2,177 +2,537 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at http://mozilla.org/MPL/2.0/.
 
-use std::path::Path;
+use std::fs::{self, DirEntry};
+use std::path::{Path, PathBuf};
 // dependencies
-use chrono::{Datelike, NaiveDate, Weekday};
+use chrono::{Datelike, NaiveDate, NaiveTime};
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 // internal
 use crate::logging;
+use crate::tasks::data::problem::TaskProblem;
 use crate::tasks::data::TaskAddable;
 use crate::tasks::task::contents::{TaskContents, TaskVisibility};
 use crate::tasks::task::meta::{
     TaskFrequency, TaskFrequencyInterval, TaskMeta, TaskMetaDisplayOptions, TaskTimeOfDay,
 };
+use crate::tasks::task::origin::{TaskOrigin, TaskType};
 use crate::tasks::task::Task;
-use crate::tasks::types;
+use crate::tasks::types::{self, DataWeekday, FnMigrate, TaskFile};
 use crate::time;
+use crate::time::recurrence::RecurrenceRule;
+use crate::time::season::{self, Season};
 
 pub(crate) const DIR_NAME: &str = "recurring";
 
-#[derive(Serialize, Deserialize)]
-struct Data {
+#[derive(Serialize, Deserialize, JsonSchema)]
+#[schemars(deny_unknown_fields)]
+pub(crate) struct Data {
+    #[serde(default)]
+    version: u32,
     title: String,
     note: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
     description: Option<String>,
 
+    #[serde(default, skip_serializing_if = "TaskFrequency::is_none")]
     frequency: TaskFrequency,
+    /// RRULE subset, instead of the frequency
+    #[serde(skip_serializing_if = "Option::is_none")]
+    rrule: Option<String>,
     last: String,
 
+    #[serde(default, skip_serializing_if = "DataScheduleMode::is_from_completion")]
+    schedule_mode: DataScheduleMode,
+    /// First occurrence of a fixed schedule
+    #[serde(skip_serializing_if = "Option::is_none")]
+    anchor: Option<String>,
+
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    history: Vec<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    snoozed_until: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
     snap_to: Option<DataSnapTo>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pivot: Option<DataPivot>,
 
-    #[serde(default = "TaskTimeOfDay::default")]
+    #[serde(
+        default = "TaskTimeOfDay::default",
+        skip_serializing_if = "TaskTimeOfDay::is_any"
+    )]
     time_of_day: TaskTimeOfDay,
 
-    #[serde(default = "types::default_zero_i32")]
+    /// Clock time as HH:MM
+    #[serde(skip_serializing_if = "Option::is_none")]
+    time: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    duration_minutes: Option<u32>,
+
+    #[serde(
+        default = "types::default_zero_i32",
+        skip_serializing_if = "types::is_zero_i32"
+    )]
     buffer_days: i32,
+    /// Days before the due date the task is listed today, with the due date kept
+    #[serde(default, skip_serializing_if = "types::is_zero_u32")]
+    lead_days: u32,
 
-    #[serde(default = "types::default_vec")]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     subtasks: Vec<DataSubtask>,
 
-    #[serde(default = "types::default_true")]
+    #[serde(
+        default = "types::default_true",
+        skip_serializing_if = "types::is_true"
+    )]
     active: bool,
+    /// Part of the year the task is active in
+    #[serde(skip_serializing_if = "Option::is_none")]
+    season: Option<DataSeason>,
+    /// Last day an occurrence can fall on
+    #[serde(skip_serializing_if = "Option::is_none")]
+    until: Option<String>,
+    /// Number of completions after which the task ends
+    #[serde(skip_serializing_if = "Option::is_none")]
+    count: Option<u32>,
 
-    #[serde(default = "types::default_false")]
+    #[serde(
+        default = "types::default_false",
+        skip_serializing_if = "types::is_false"
+    )]
     pub(crate) hidden: bool,
 }
 
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, JsonSchema)]
+#[schemars(deny_unknown_fields)]
 pub(crate) struct DataSubtask {
     pub(crate) title: String,
     pub(crate) done: String,
 
-    #[serde(default = "types::default_false")]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub(crate) history: Vec<String>,
+
+    #[serde(
+        default = "types::default_false",
+        skip_serializing_if = "types::is_false"
+    )]
+    pub(crate) persistent: bool,
+
+    #[serde(
+        default = "types::default_false",
+        skip_serializing_if = "types::is_false"
+    )]
     pub(crate) hidden: bool,
 }
 
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, JsonSchema)]
+#[schemars(deny_unknown_fields)]
+struct DataSeason {
+    /// First day, as MM-DD
+    from: String,
+    /// Last day, as MM-DD
+    to: String,
+}
+
+#[derive(Default, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+enum DataScheduleMode {
+    /// The next occurrence follows the last completion
+    #[default]
+    FromCompletion,
+    /// Occurrences follow the anchor date, whenever they are done
+    Fixed,
+}
+
+impl DataScheduleMode {
+    fn is_from_completion(&self) -> bool {
+        return *self == DataScheduleMode::FromCompletion;
+    }
+}
+
+#[derive(Serialize, Deserialize, JsonSchema)]
 enum DataSnapTo {
     ToBeDetermined,
     Today,
 }
 
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, JsonSchema)]
+#[schemars(deny_unknown_fields)]
 struct DataPivot {
+    #[serde(skip_serializing_if = "Option::is_none")]
     weekday: Option<DataWeekday>,
 }
 
-#[derive(Serialize, Deserialize)]
-enum DataWeekday {
-    Mon,
-    Tue,
-    Wed,
-    Thu,
-    Fri,
-    Sat,
-    Sun,
-}
-
-impl DataWeekday {
-    fn to_chrono_weekday(&self) -> Weekday {
-        return match self {
-            DataWeekday::Mon => Weekday::Mon,
-            DataWeekday::Tue => Weekday::Tue,
-            DataWeekday::Wed => Weekday::Wed,
-            DataWeekday::Thu => Weekday::Thu,
-            DataWeekday::Fri => Weekday::Fri,
-            DataWeekday::Sat => Weekday::Sat,
-            DataWeekday::Sun => Weekday::Sun,
-        };
+/// Number of frequency steps searched for an occurrence after a date,
+/// from the last step known to be before it
+const FREQUENCY_STEP_MAX: u32 = 1000;
+/// Missed occurrences of a fixed schedule listed as separate entries, older ones are left out
+const MISSED_OCCURRENCE_MAX: usize = 100;
+
+fn next_date(data: &Data, file_path: &Path) -> Result<NaiveDate, TaskProblem> {
+    let last_date: NaiveDate = match time::parsing::date_from_str(&data.last) {
+        Err(reason) => {
+            return Err(TaskProblem::new(file_path, "last", &data.title, reason));
+        }
+        Ok(date) => date,
+    };
+    return occurrence_after(data, file_path, &last_date, &last_date);
+}
+
+/// Dates of the entries of the task, in order
+fn occurrence_dates(
+    data: &Data,
+    file_path: &Path,
+    today: &NaiveDate,
+) -> Result<Vec<NaiveDate>, TaskProblem> {
+    return match data.schedule_mode {
+        DataScheduleMode::FromCompletion => next_date(data, file_path).map(|date| vec![date]),
+        DataScheduleMode::Fixed => fixed_dates(data, file_path, today),
+    };
+}
+
+/// Outstanding occurrences of a fixed schedule: the missed ones before today followed by the next one
+fn fixed_dates(
+    data: &Data,
+    file_path: &Path,
+    today: &NaiveDate,
+) -> Result<Vec<NaiveDate>, TaskProblem> {
+    let anchor_date: NaiveDate = anchor_date(data, file_path)?;
+    let mut after: NaiveDate = outstanding_after(data, file_path, &anchor_date)?;
+
+    let mut dates: Vec<NaiveDate> = Vec::new();
+    loop {
+        let date: NaiveDate = occurrence_after(data, file_path, &anchor_date, &after)?;
+        dates.push(date);
+        if date >= *today {
+            break;
+        }
+        after = date;
+    }
+
+    if dates.len() > MISSED_OCCURRENCE_MAX + 1 {
+        logging::warning(format!(
+            "Only the {} most recent missed occurrences are listed: '{}'",
+            MISSED_OCCURRENCE_MAX, data.title
+        ));
+        dates.drain(..dates.len() - MISSED_OCCURRENCE_MAX - 1);
     }
+    return Ok(dates);
 }
 
-pub(crate) fn load_one(file_path: &Path, task_data: &mut dyn TaskAddable) {
-    let data: Data = match types::load(file_path) {
-        None => {
-            return;
+/// Later occurrences within the dated window, as if each one is done on time
+fn projected_dates(
+    data: &Data,
+    file_path: &Path,
+    next_date: &NaiveDate,
+    snoozed_until_opt: Option<NaiveDate>,
+    today: &NaiveDate,
+    last_dated: &NaiveDate,
+) -> Vec<NaiveDate> {
+    // an overdue or snoozed occurrence is done today or when the snooze ends at the earliest
+    let after_date: NaiveDate = (*next_date)
+        .max(*today)
+        .max(snoozed_until_opt.unwrap_or(NaiveDate::MIN));
+    let start_date: NaiveDate = match data.schedule_mode {
+        DataScheduleMode::FromCompletion => after_date,
+        DataScheduleMode::Fixed => match anchor_date(data, file_path) {
+            Err(_) => return Vec::new(),
+            Ok(date) => date,
+        },
+    };
+
+    let mut dates: Vec<NaiveDate> = Vec::new();
+    let mut after: NaiveDate = after_date;
+    // the series is valid, it gave the next date
+    while let Ok(date) = occurrence_after(data, file_path, &start_date, &after) {
+        if date > *last_dated {
+            break;
         }
-        Some(data) => data,
+        dates.push(date);
+        after = date;
+    }
+    return dates;
+}
+
+/// What the task is listed by, the task is left out when any part of it is invalid
+struct TaskSchedule {
+    occurrence_dates: Vec<NaiveDate>,
+    season_opt: Option<Season>,
+    until_opt: Option<NaiveDate>,
+}
+
+/// The checks of a task file both when loading and creating it
+fn schedule_of(
+    data: &Data,
+    file_path: &Path,
+    today: &NaiveDate,
+) -> Result<TaskSchedule, TaskProblem> {
+    return Ok(TaskSchedule {
+        occurrence_dates: occurrence_dates(data, file_path, today)?,
+        season_opt: season_of(data, file_path)?,
+        until_opt: until_of(data, file_path)?,
+    });
+}
+
+fn until_of(data: &Data, file_path: &Path) -> Result<Option<NaiveDate>, TaskProblem> {
+    return match &data.until {
+        None => Ok(None),
+        Some(date_string) => time::parsing::date_from_str(date_string)
+            .map(Some)
+            .map_err(|reason| TaskProblem::new(file_path, "until", &data.title, reason)),
     };
+}
 
-    let last_date = match NaiveDate::parse_from_str(data.last.as_str(), "%Y-%m-%d") {
-        Err(_) => {
-            logging::error(format!(
-                "Failed to convert last date in recurring task: '{}' ({})",
-                data.last, data.title
-            ));
-            return;
+fn season_of(data: &Data, file_path: &Path) -> Result<Option<Season>, TaskProblem> {
+    let data_season: &DataSeason = match &data.season {
+        None => return Ok(None),
+        Some(data_season) => data_season,
+    };
+    let problem = |json_path: &str, reason: String| -> TaskProblem {
+        return TaskProblem::new(file_path, json_path, &data.title, reason);
+    };
+    let from: (u32, u32) = season::month_day_from_str(&data_season.from)
+        .map_err(|reason| problem("season.from", reason))?;
+    let to: (u32, u32) = season::month_day_from_str(&data_season.to)
+        .map_err(|reason| problem("season.to", reason))?;
+    return Ok(Some(Season::new(from, to)));
+}
+
+fn anchor_date(data: &Data, file_path: &Path) -> Result<NaiveDate, TaskProblem> {
+    let problem = |reason: String| -> TaskProblem {
+        return TaskProblem::new(file_path, "anchor", &data.title, reason);
+    };
+    return match &data.anchor {
+        None => Err(problem("Fixed schedule needs an anchor date".to_string())),
+        Some(anchor) => time::parsing::date_from_str(anchor).map_err(problem),
+    };
+}
+
+/// Occurrences of a fixed schedule after the returned date are not done yet
+fn outstanding_after(
+    data: &Data,
+    file_path: &Path,
+    anchor_date: &NaiveDate,
+) -> Result<NaiveDate, TaskProblem> {
+    // 'last' is the latest occurrence done, empty when none is
+    let last_date_opt: Option<NaiveDate> = if data.last.is_empty() {
+        None
+    } else {
+        match time::parsing::date_from_str(&data.last) {
+            Err(reason) => {
+                return Err(TaskProblem::new(file_path, "last", &data.title, reason));
+            }
+            Ok(date) => Some(date),
         }
-        Ok(date) => date,
     };
+    return Ok(match last_date_opt {
+        Some(last_date) if last_date >= *anchor_date => last_date,
+        _ => anchor_date.pred_opt().unwrap_or(*anchor_date),
+    });
+}
+
+/// First occurrence of the series beginning at `start` which falls after `after`
+fn occurrence_after(
+    data: &Data,
+    file_path: &Path,
+    start: &NaiveDate,
+    after: &NaiveDate,
+) -> Result<NaiveDate, TaskProblem> {
+    let problem = |json_path: &str, reason: String| -> TaskProblem {
+        return TaskProblem::new(file_path, json_path, &data.title, reason);
+    };
+
+    if let Some(rrule) = &data.rrule {
+        if !data.frequency.is_none() {
+            return Err(problem(
+                "rrule",
+                "Use either a frequency or an RRULE".to_string(),
+            ));
+        }
+        let rule: RecurrenceRule = match RecurrenceRule::parse(rrule) {
+            Err(reason) => return Err(problem("rrule", reason)),
+            Ok(rule) => rule,
+        };
+        return match rule.next_after(start, after) {
+            None => Err(problem(
+                "rrule",
+                format!("No occurrence after {}", after.format("%Y-%m-%d")),
+            )),
+            Some(date) => Ok(date),
+        };
+    }
 
     let frequency_number: u8 = match data.frequency.number {
         None => {
-            logging::error(format!("Missing frequency number ({})", data.title));
-            return;
+            return Err(problem(
+                "frequency.number",
+                "Missing frequency number".to_string(),
+            ));
         }
         Some(number) => number,
     };
 
     if frequency_number < 1 {
-        logging::error(format!("Frequency number cannot be zero ({})", data.title));
-        return;
+        return Err(problem(
+            "frequency.number",
+            "Frequency number cannot be zero".to_string(),
+        ));
+    }
+
+    let (interval, interval_count): (TaskFrequencyInterval, u32) =
+        match data.frequency.interval.resolve() {
+            None => {
+                let reason: String = match &data.frequency.interval {
+                    TaskFrequencyInterval::Other(name) => {
+                        format!("No named interval '{}' in the config", name)
+                    }
+                    _ => "Unable to parse task frequency".to_string(),
+                };
+                return Err(problem("frequency.interval", reason));
+            }
+            Some(resolved) => resolved,
+        };
+
+    // the steps before the date are skipped, the start can be far in the past
+    let step_size: u32 = frequency_number as u32 * interval_count;
+    let step_first: u32 = intervals_until(start, after, &interval) / step_size;
+    for step in step_first..step_first.saturating_add(FREQUENCY_STEP_MAX) {
+        let count: u32 = step.saturating_mul(step_size);
+        let date: NaiveDate = match add_intervals(start, &interval, count) {
+            None => {
+                return Err(problem(
+                    "frequency.interval",
+                    "Unable to parse task frequency".to_string(),
+                ));
+            }
+            Some(date) => date,
+        };
+        if date > *after {
+            return Ok(date);
+        }
     }
+    return Err(problem(
+        "frequency",
+        format!("No occurrence after {}", after.format("%Y-%m-%d")),
+    ));
+}
 
-    let task_date_option: Option<NaiveDate> = match data.frequency.interval {
+/// Whole intervals from the start date which do not pass the given date, zero before the start
+fn intervals_until(start: &NaiveDate, date: &NaiveDate, interval: &TaskFrequencyInterval) -> u32 {
+    let days: i64 = (*date - *start).num_days();
+    // one month less, as the day of the month may not be reached yet
+    let months: i64 = time::months_between(start, date) - 1;
+    let count: i64 = match interval {
+        TaskFrequencyInterval::Day => days,
+        TaskFrequencyInterval::Week => days / 7,
+        TaskFrequencyInterval::Month => months,
+        TaskFrequencyInterval::Year => months / 12,
+        TaskFrequencyInterval::Other(_) | TaskFrequencyInterval::None => 0,
+    };
+    return u32::try_from(count.max(0)).unwrap_or(u32::MAX);
+}
+
+fn add_intervals(
+    date: &NaiveDate,
+    interval: &TaskFrequencyInterval,
+    count: u32,
+) -> Option<NaiveDate> {
+    // counted from the start, a month end is not shortened for good by a shorter month
+    return match interval {
         TaskFrequencyInterval::Other(_) => None,
-        TaskFrequencyInterval::Day => time::add_days(&last_date, frequency_number),
-        TaskFrequencyInterval::Week => time::add_weeks(&last_date, frequency_number),
-        TaskFrequencyInterval::Month => time::add_months(&last_date, frequency_number),
-        TaskFrequencyInterval::Year => time::add_years(&last_date, frequency_number),
+        TaskFrequencyInterval::Day => time::add_days(date, count),
+        TaskFrequencyInterval::Week => time::add_weeks(date, count),
+        TaskFrequencyInterval::Month => time::add_months(date, count),
+        TaskFrequencyInterval::Year => time::add_years(date, count),
         TaskFrequencyInterval::None => None,
     };
+}
 
-    let mut task_date: NaiveDate = match task_date_option {
+impl TaskFile for Data {
+    const MIGRATIONS: &'static [FnMigrate] = &[types::migrate_to_versioned];
+
+    fn normalize(&mut self) {
+        types::normalize_date(&mut self.last);
+        if let Some(anchor) = &mut self.anchor {
+            types::normalize_date(anchor);
+        }
+        if let Some(until) = &mut self.until {
+            types::normalize_date(until);
+        }
+        if let Some(time) = &mut self.time {
+            types::normalize_time(time);
+        }
+        self.history.iter_mut().for_each(types::normalize_date);
+        if let Some(snoozed_until) = &mut self.snoozed_until {
+            types::normalize_date(snoozed_until);
+        }
+        for subtask in &mut self.subtasks {
+            types::normalize_date(&mut subtask.done);
+            subtask.history.iter_mut().for_each(types::normalize_date);
+        }
+    }
+}
+
+pub(crate) fn load_one(file_path: &Path, task_data: &mut dyn TaskAddable) {
+    let data: Data = match types::load_or_report(file_path, task_data) {
         None => {
-            logging::error(format!("Unable to parse task frequency ({})", data.title));
             return;
         }
-        Some(date) => date,
+        Some(data) => data,
     };
 
     let today: NaiveDate = task_data.date_today();
 
-    if let Some(pivot) = data.pivot {
-        if let Some(weekday) = pivot.weekday {
-            while task_date.weekday() != weekday.to_chrono_weekday() {
-                task_date = time::increment_by_one_day(&task_date);
-            }
+    let TaskSchedule {
+        mut occurrence_dates,
+        season_opt,
+        until_opt,
+    } = match schedule_of(&data, file_path, &today) {
+        Err(problem) => {
+            task_data.add_problem(problem);
+            return;
         }
-    }
+        Ok(schedule) => schedule,
+    };
 
-    if data.buffer_days != 0 {
-        task_date = time::adjust_by_buffer_days(&task_date, data.buffer_days)
-            .expect("Failed to subtract day.");
+    for (history_index, date_string) in data.history.iter().enumerate() {
+        if let Err(reason) = time::parsing::date_from_str(date_string) {
+            task_data.add_problem(TaskProblem::new(
+                file_path,
+                &format!("history[{}]", history_index),
+                &data.title,
+                reason,
+            ));
+        }
     }
+    let history: Vec<NaiveDate> = parse_history(&data);
 
-    let overdue: bool = task_date < today;
-
-    if let Some(snap_to) = data.snap_to {
-        match snap_to {
-            DataSnapTo::Today => {
-                if task_date < today {
-                    task_date = today;
-                }
+    let snoozed_until: Option<NaiveDate> = match &data.snoozed_until {
+        None => None,
+        Some(date_string) => match time::parsing::date_from_str(date_string) {
+            Err(reason) => {
+                task_data.add_problem(TaskProblem::new(
+                    file_path,
+                    "snoozed_until",
+                    &data.title,
+                    reason,
+                ));
+                None
             }
-            DataSnapTo::ToBeDetermined => {}
-        }
-    }
+            Ok(date) => Some(date),
+        },
+    };
+
+    let start_time: Option<NaiveTime> =
+        types::start_time_or_report(&data.time, file_path, "time", &data.title, task_data);
 
-    let subtasks: Vec<TaskContents> = data
+    let mut subtasks: Vec<TaskContents> = data
         .subtasks
         .iter()
-        .map(|subtask| TaskContents {
+        .enumerate()
+        .map(|(subtask_index, subtask)| TaskContents {
             title: subtask.title.clone(),
             note: "".to_string(),
             is_done: !subtask.done.is_empty(),
@@ -181,6 +541,7 @@ pub(crate) fn load_one(file_path: &Path, task_data: &mut dyn TaskAddable) {
             } else {
                 TaskVisibility::Visible
             },
+            origin_index: Some(subtask_index),
         })
         .collect();
 
@@ -192,23 +553,474 @@ pub(crate) fn load_one(file_path: &Path, task_data: &mut dyn TaskAddable) {
         task_visibility = TaskVisibility::Hidden;
     }
 
-    let task: Task = Task {
-        meta: TaskMeta {
-            frequency: data.frequency,
-            time_of_day: data.time_of_day,
-            overdue,
-            subtasks,
-            display_options: TaskMetaDisplayOptions {
-                overdue_mark: task_date == today && data.active,
-            },
-        },
-        contents: TaskContents {
-            title: data.title,
-            note: data.note,
-            is_done: false,
-            visibility: task_visibility,
+    // the rule is valid, it was used for the task date
+    let frequency: TaskFrequency = match data.rrule.as_deref().map(RecurrenceRule::parse) {
+        Some(Ok(rule)) => TaskFrequency {
+            number: None,
+            interval: TaskFrequencyInterval::Other(rule.to_string()),
         },
+        _ => data.frequency.clone(),
     };
 
-    task_data.add_task(task_date, task);
+    if let Some(season) = &season_opt {
+        if !season.contains(&today) && task_visibility == TaskVisibility::Visible {
+            task_visibility = TaskVisibility::Inactive;
+        }
+        // an occurrence out of season waits for the start of the next one
+        occurrence_dates = occurrence_dates
+            .iter()
+            .map(|date| season.first_day_from(date).unwrap_or(*date))
+            .collect();
+        occurrence_dates.dedup();
+    }
+
+    // completions left before the task ends
+    let remaining_opt: Option<usize> = data
+        .count
+        .map(|count| (count as usize).saturating_sub(history.len()));
+    if let Some(until) = until_opt {
+        occurrence_dates.retain(|date| *date <= until);
+    }
+    if let Some(remaining) = remaining_opt {
+        occurrence_dates.truncate(remaining);
+    }
+
+    let ended_opt: Option<String> = match (remaining_opt, until_opt) {
+        (Some(0), _) => Some(format!(
+            "done {} of {} times",
+            history.len(),
+            data.count.unwrap_or_default()
+        )),
+        (_, Some(until)) if occurrence_dates.is_empty() => {
+            Some(format!("until {}", until.format("%Y-%m-%d")))
+        }
+        _ => None,
+    };
+    if ended_opt.is_some() {
+        // listed once, without a date
+        occurrence_dates = vec![today];
+        if task_visibility == TaskVisibility::Visible {
+            task_visibility = TaskVisibility::Inactive;
+        }
+    }
+
+    let outstanding_count: usize = occurrence_dates.len();
+    if task_visibility == TaskVisibility::Visible {
+        let next_date: NaiveDate = occurrence_dates[outstanding_count - 1];
+        let last_dated: NaiveDate = task_data
+            .date_last_dated()
+            .min(until_opt.unwrap_or(NaiveDate::MAX));
+        let mut projected_dates: Vec<NaiveDate> = projected_dates(
+            &data,
+            file_path,
+            &next_date,
+            snoozed_until,
+            &today,
+            &last_dated,
+        );
+        if let Some(season) = &season_opt {
+            projected_dates.retain(|date| season.contains(date) && *date > next_date);
+        }
+        occurrence_dates.extend(projected_dates);
+        if let Some(remaining) = remaining_opt {
+            occurrence_dates.truncate(remaining);
+        }
+    }
+
+    for (occurrence_index, occurrence_date) in occurrence_dates.iter().enumerate() {
+        let mut task_date: NaiveDate = *occurrence_date;
+        let projected: bool = occurrence_index >= outstanding_count;
+
+        if let Some(pivot) = &data.pivot {
+            if let Some(weekday) = &pivot.weekday {
+                while task_date.weekday() != weekday.to_chrono_weekday() {
+                    task_date = time::increment_by_one_day(&task_date);
+                }
+            }
+        }
+
+        if data.buffer_days != 0 {
+            task_date = time::adjust_by_buffer_days(&task_date, data.buffer_days)
+                .expect("Failed to subtract day.");
+        }
+
+        let overdue: bool = task_date < today;
+
+        // a missed occurrence of a fixed schedule stays a separate entry at its own date
+        let is_missed: bool = *occurrence_date < today && occurrence_index + 1 < outstanding_count;
+        if let (Some(DataSnapTo::Today), false) = (&data.snap_to, is_missed) {
+            if task_date < today {
+                task_date = today;
+            }
+        }
+
+        // within the lead time, the task is listed today, counting down to its due date
+        let days_until_due: i64 = (task_date - today).num_days();
+        let due_in_days: Option<i64> =
+            if !projected && days_until_due > 0 && days_until_due <= data.lead_days as i64 {
+                task_date = today;
+                Some(days_until_due)
+            } else {
+                None
+            };
+
+        // subtasks and snooze belong to the earliest outstanding occurrence, the next one done
+        let is_earliest: bool = occurrence_index == 0;
+
+        let task: Task = Task {
+            meta: TaskMeta {
+                frequency: frequency.clone(),
+                time_of_day: data.time_of_day.clone(),
+                start_time,
+                duration_minutes: data.duration_minutes,
+                overdue,
+                projected,
+                due_in_days,
+                ended: ended_opt.clone(),
+                subtasks: if is_earliest {
+                    std::mem::take(&mut subtasks)
+                } else {
+                    Vec::new()
+                },
+                history: history.clone(),
+                snoozed_until: if is_earliest { snoozed_until } else { None },
+                display_options: TaskMetaDisplayOptions {
+                    overdue_mark: task_date == today && data.active,
+                },
+            },
+            contents: TaskContents {
+                title: data.title.clone(),
+                note: data.note.clone(),
+                is_done: false,
+                visibility: task_visibility,
+                origin_index: None,
+            },
+            origin: TaskOrigin::new(TaskType::Recurring, file_path),
+        };
+
+        task_data.add_task(task_date, task);
+    }
+}
+
+pub(crate) fn create(dir_path: &Path, definition: serde_json::Value) -> Option<PathBuf> {
+    let mut data: Data = match serde_json::from_value(definition) {
+        Err(why) => {
+            logging::error(format!("Invalid recurring task definition \n{}", why));
+            return None;
+        }
+        Ok(data) => data,
+    };
+    data.version = types::version_current::<Data>();
+
+    let file_stem: String = types::file_stem_of(&data.title);
+    if file_stem.is_empty() {
+        logging::error(format!(
+            "Title of recurring task cannot be used as a file name: '{}'",
+            data.title
+        ));
+        return None;
+    }
+    let file_path: PathBuf = dir_path.join(format!("{}.json", file_stem));
+    if file_path.exists() {
+        logging::error(format!(
+            "Recurring task file '{}' already exists",
+            file_path.display()
+        ));
+        return None;
+    }
+
+    if let Some(time) = &data.time {
+        if let Err(reason) = time::parsing::time_from_str(time) {
+            logging::error(reason);
+            return None;
+        }
+    }
+
+    // the same rules as when loading, so that the new file is not rejected later
+    let next_date: NaiveDate = match schedule_of(&data, &file_path, &time::today()) {
+        Err(problem) => {
+            logging::error(problem.to_string());
+            return None;
+        }
+        Ok(schedule) => schedule.occurrence_dates[0],
+    };
+
+    logging::info(format!(
+        "Creating recurring task '{}', next on {}",
+        data.title,
+        next_date.format("%Y-%m-%d")
+    ));
+    return match types::save(&file_path, &data) {
+        true => Some(file_path),
+        false => None,
+    };
+}
+
+pub(crate) fn find(dir_path: &Path, query: &str) -> Vec<PathBuf> {
+    let mut matches_file_name: Vec<PathBuf> = Default::default();
+    let mut matches_title: Vec<PathBuf> = Default::default();
+
+    let entries = match fs::read_dir(dir_path) {
+        Err(why) => {
+            logging::error(format!(
+                "Couldn't read recurring task dir '{}' \n{}",
+                dir_path.display(),
+                why
+            ));
+            return Default::default();
+        }
+        Ok(entries) => entries,
+    };
+
+    let query_lowercase: String = query.to_lowercase();
+    for entry in entries {
+        let entry: DirEntry = entry.expect("Failed to iterate dir entry.");
+        let entry_path: PathBuf = entry.path();
+        if entry_path.is_dir() {
+            continue;
+        }
+
+        let file_name_matches: bool = entry_path
+            .file_name()
+            .is_some_and(|file_name| file_name.to_string_lossy() == query)
+            || entry_path
+                .file_stem()
+                .is_some_and(|file_stem| file_stem.to_string_lossy() == query);
+        if file_name_matches {
+            matches_file_name.push(entry_path);
+            continue;
+        }
+
+        if let Some(data) = types::load::<Data>(&entry_path) {
+            if data.title.to_lowercase().contains(&query_lowercase) {
+                matches_title.push(entry_path);
+            }
+        }
+    }
+
+    if !matches_file_name.is_empty() {
+        return matches_file_name;
+    }
+    matches_title.sort();
+    return matches_title;
+}
+
+pub(crate) fn mark_done(file_path: &Path, date: &NaiveDate) -> bool {
+    let mut data: Data = match types::load_to_rewrite(file_path) {
+        None => {
+            return false;
+        }
+        Some(data) => data,
+    };
+
+    let date_string: String = date.format("%Y-%m-%d").to_string();
+    logging::info(format!(
+        "Marking recurring task done on {}: '{}' (last: {})",
+        date_string, data.title, data.last
+    ));
+
+    if data.schedule_mode == DataScheduleMode::Fixed {
+        return mark_occurrence_done(file_path, data, date);
+    }
+
+    // compared as dates, as the file may have them without the leading zeros
+    let last_date_opt: Option<NaiveDate> = match parse_last(&data) {
+        Err(reason) => {
+            logging::error(format!(
+                "Couldn't mark recurring task done: {} ({})",
+                reason, data.title
+            ));
+            return false;
+        }
+        Ok(last_date_opt) => last_date_opt,
+    };
+
+    // a completion older than 'last' is only a correction of the past cycles
+    let starts_new_cycle: bool = last_date_opt.is_none_or(|last_date| *date >= last_date);
+    if starts_new_cycle {
+        reset_subtasks(&mut data.subtasks);
+        data.snoozed_until = None;
+    }
+
+    let mut history: Vec<NaiveDate> = Vec::new();
+    for date_string in &data.history {
+        match time::parsing::date_from_str(date_string) {
+            Err(reason) => {
+                logging::error(format!(
+                    "Couldn't mark recurring task done: {} ({})",
+                    reason, data.title
+                ));
+                return false;
+            }
+            Ok(date) => history.push(date),
+        }
+    }
+
+    // the previous 'last' is a real completion as well, keep it when starting the history
+    if history.is_empty() {
+        history.extend(last_date_opt);
+    }
+    if !history.contains(date) {
+        history.push(*date);
+    }
+    history.sort();
+    data.history = history
+        .iter()
+        .map(|date| date.format("%Y-%m-%d").to_string())
+        .collect();
+    // 'last' stays the most recent completion, even when a past one was added
+    if let Some(most_recent) = data.history.last() {
+        data.last = most_recent.clone();
+    }
+
+    return types::save(file_path, &data);
+}
+
+/// The latest completion, none when the task was never done
+fn parse_last(data: &Data) -> Result<Option<NaiveDate>, String> {
+    if data.last.is_empty() {
+        return Ok(None);
+    }
+    return time::parsing::date_from_str(&data.last).map(Some);
+}
+
+/// Any completion of a fixed schedule is for its earliest outstanding occurrence
+fn mark_occurrence_done(file_path: &Path, mut data: Data, date: &NaiveDate) -> bool {
+    let occurrence_result: Result<NaiveDate, TaskProblem> =
+        anchor_date(&data, file_path).and_then(|anchor_date| {
+            let after: NaiveDate = outstanding_after(&data, file_path, &anchor_date)?;
+            return occurrence_after(&data, file_path, &anchor_date, &after);
+        });
+    let occurrence_date: NaiveDate = match occurrence_result {
+        Err(problem) => {
+            logging::error(format!("Couldn't mark recurring task done: {}", problem));
+            return false;
+        }
+        Ok(date) => date,
+    };
+
+    logging::info(format!(
+        "Completing the occurrence of {} ({})",
+        occurrence_date.format("%Y-%m-%d"),
+        data.title
+    ));
+    reset_subtasks(&mut data.subtasks);
+    data.snoozed_until = None;
+
+    // the history has the completion dates, one entry per occurrence done,
+    // also when several are settled on the same day; 'last' has the occurrence
+    data.history.push(date.format("%Y-%m-%d").to_string());
+    data.history.sort();
+    data.last = occurrence_date.format("%Y-%m-%d").to_string();
+
+    return types::save(file_path, &data);
+}
+
+pub(crate) fn set_snoozed_until(file_path: &Path, date_opt: Option<&NaiveDate>) -> bool {
+    let mut data: Data = match types::load_to_rewrite(file_path) {
+        None => {
+            return false;
+        }
+        Some(data) => data,
+    };
+
+    data.snoozed_until = date_opt.map(|date| date.format("%Y-%m-%d").to_string());
+
+    return types::save(file_path, &data);
+}
+
+fn reset_subtasks(subtasks: &mut [DataSubtask]) {
+    for subtask in subtasks.iter_mut() {
+        if subtask.persistent || subtask.done.is_empty() {
+            continue;
+        }
+        let done: String = std::mem::take(&mut subtask.done);
+        subtask.history.push(done);
+    }
+}
+
+pub(crate) fn load_history(file_path: &Path) -> Option<(String, Vec<NaiveDate>)> {
+    let data: Data = types::load(file_path)?;
+    return Some((data.title.clone(), parse_history(&data)));
+}
+
+fn parse_history(data: &Data) -> Vec<NaiveDate> {
+    // invalid entries are reported when loading the task
+    let mut history: Vec<NaiveDate> = data
+        .history
+        .iter()
+        .filter_map(|date_string| time::parsing::date_from_str(date_string).ok())
+        .collect();
+    if history.is_empty() {
+        if let Ok(last_date) = NaiveDate::parse_from_str(data.last.as_str(), "%Y-%m-%d") {
+            history.push(last_date);
+        }
+    }
+    // kept with the duplicates, as for settling several occurrences on the same day
+    history.sort();
+    return history;
+}
+
+pub(crate) fn toggle_subtask_done(
+    file_path: &Path,
+    subtask_index: usize,
+    date: &NaiveDate,
+) -> bool {
+    let mut data: Data = match types::load_to_rewrite(file_path) {
+        None => {
+            return false;
+        }
+        Some(data) => data,
+    };
+
+    let subtask: &mut DataSubtask = match data.subtasks.get_mut(subtask_index) {
+        None => {
+            logging::error(format!(
+                "No subtask with index {} in recurring task ({})",
+                subtask_index, data.title
+            ));
+            return false;
+        }
+        Some(subtask) => subtask,
+    };
+
+    if subtask.done.is_empty() {
+        subtask.done = date.format("%Y-%m-%d").to_string();
+    } else {
+        subtask.done.clear();
+    }
+
+    return types::save(file_path, &data);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        return NaiveDate::from_ymd_opt(year, month, day).expect("Invalid test date");
+    }
+
+    #[test]
+    fn intervals_until_do_not_pass_the_date() {
+        let start: NaiveDate = date(1990, 1, 31);
+        let after: NaiveDate = date(2026, 10, 10);
+        for interval in [
+            TaskFrequencyInterval::Day,
+            TaskFrequencyInterval::Week,
+            TaskFrequencyInterval::Month,
+            TaskFrequencyInterval::Year,
+        ] {
+            let count: u32 = intervals_until(&start, &after, &interval);
+            let date: NaiveDate = add_intervals(&start, &interval, count).expect("No date");
+            let date_next: NaiveDate =
+                add_intervals(&start, &interval, count + 2).expect("No date");
+            assert!(date <= after);
+            assert!(date_next > after);
+        }
+        assert_eq!(
+            intervals_until(&after, &start, &TaskFrequencyInterval::Day),
+            0
+        );
+    }
 }
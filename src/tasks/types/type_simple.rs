@@ -2,60 +2,105 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at http://mozilla.org/MPL/2.0/.
 
-use std::path::Path;
+use std::fs::{self, DirEntry};
+use std::path::{Path, PathBuf};
 // dependencies
-use chrono::NaiveDate;
+use chrono::{NaiveDate, NaiveTime};
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
 // internal
 use crate::logging;
+use crate::tasks::data::problem::TaskProblem;
 use crate::tasks::data::TaskAddable;
 use crate::tasks::task::contents::{TaskContents, TaskVisibility};
 use crate::tasks::task::meta::{
     TaskFrequency, TaskFrequencyInterval, TaskMeta, TaskMetaDisplayOptions, TaskTimeOfDay,
 };
+use crate::tasks::task::origin::{TaskOrigin, TaskType};
 use crate::tasks::task::Task;
-use crate::tasks::types;
+use crate::tasks::types::{self, FnMigrate, TaskFile};
+use crate::time;
 
 pub(crate) const DIR_NAME: &str = "simple";
 
-#[derive(Serialize, Deserialize)]
-struct Data {
+#[derive(Serialize, Deserialize, JsonSchema)]
+#[schemars(deny_unknown_fields)]
+pub(crate) struct Data {
+    #[serde(default)]
+    version: u32,
     prefix: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
     description: Option<String>,
     items: Vec<DataItem>,
 }
 
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, JsonSchema)]
+#[schemars(deny_unknown_fields)]
 struct DataItem {
     title: String,
     note: String,
     due: String,
     done: String,
 
-    #[serde(default = "TaskTimeOfDay::default")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    snoozed_until: Option<String>,
+
+    #[serde(
+        default = "TaskTimeOfDay::default",
+        skip_serializing_if = "TaskTimeOfDay::is_any"
+    )]
     time_of_day: TaskTimeOfDay,
+
+    /// Clock time as HH:MM
+    #[serde(skip_serializing_if = "Option::is_none")]
+    time: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    duration_minutes: Option<u32>,
+}
+
+impl TaskFile for Data {
+    const MIGRATIONS: &'static [FnMigrate] = &[types::migrate_to_versioned];
+
+    fn normalize(&mut self) {
+        for item in &mut self.items {
+            types::normalize_date(&mut item.due);
+            types::normalize_date(&mut item.done);
+            if let Some(snoozed_until) = &mut item.snoozed_until {
+                types::normalize_date(snoozed_until);
+            }
+            if let Some(time) = &mut item.time {
+                types::normalize_time(time);
+            }
+        }
+    }
 }
 
 pub(crate) fn load(file_path: &Path, task_data: &mut dyn TaskAddable) {
-    let data: Data = match types::load(file_path) {
+    let data: Data = match types::load_or_report(file_path, task_data) {
         None => {
             return;
         }
         Some(data) => data,
     };
 
-    for item in data.items {
+    for (item_index, item) in data.items.into_iter().enumerate() {
         if !item.done.is_empty() {
             continue;
         }
 
-        let due_date = match NaiveDate::parse_from_str(item.due.as_str(), "%Y-%m-%d") {
-            Err(_) => {
-                logging::error(format!(
-                    "Failed to convert due date in simple task: '{}' ({})",
-                    item.due, item.title
+        let json_path: String = format!("items[{}]", item_index);
+
+        let due_date: NaiveDate = match time::parsing::date_from_str(&item.due) {
+            Err(reason) => {
+                // only this item is skipped, the later ones are still loaded
+                task_data.add_problem(TaskProblem::new(
+                    file_path,
+                    &format!("{}.due", json_path),
+                    &item.title,
+                    reason,
                 ));
-                return;
+                continue;
             }
             Ok(date) => date,
         };
@@ -63,6 +108,30 @@ pub(crate) fn load(file_path: &Path, task_data: &mut dyn TaskAddable) {
         let today: NaiveDate = task_data.date_today();
         let overdue: bool = due_date < today;
 
+        let snoozed_until: Option<NaiveDate> = match &item.snoozed_until {
+            None => None,
+            Some(date_string) => match time::parsing::date_from_str(date_string) {
+                Err(reason) => {
+                    task_data.add_problem(TaskProblem::new(
+                        file_path,
+                        &format!("{}.snoozed_until", json_path),
+                        &item.title,
+                        reason,
+                    ));
+                    None
+                }
+                Ok(date) => Some(date),
+            },
+        };
+
+        let start_time: Option<NaiveTime> = types::start_time_or_report(
+            &item.time,
+            file_path,
+            &format!("{}.time", json_path),
+            &item.title,
+            task_data,
+        );
+
         let title: String = format!("{}  >>  {}", data.prefix, item.title);
 
         let is_done: bool = !item.done.is_empty();
@@ -79,8 +148,15 @@ pub(crate) fn load(file_path: &Path, task_data: &mut dyn TaskAddable) {
                     interval: TaskFrequencyInterval::None,
                 },
                 time_of_day: item.time_of_day,
+                start_time,
+                duration_minutes: item.duration_minutes,
                 overdue,
+                projected: false,
+                due_in_days: None,
+                ended: None,
                 subtasks: Default::default(),
+                history: Default::default(),
+                snoozed_until,
                 display_options: TaskMetaDisplayOptions {
                     overdue_mark: due_date == today,
                 },
@@ -90,9 +166,303 @@ pub(crate) fn load(file_path: &Path, task_data: &mut dyn TaskAddable) {
                 note: item.note,
                 is_done,
                 visibility,
+                origin_index: None,
             },
+            origin: TaskOrigin::new(TaskType::Simple, file_path).with_item(item_index),
         };
 
         task_data.add_task(due_date, task);
     }
 }
+
+fn parse_due(due: &str, title: &str) -> Option<NaiveDate> {
+    return time::parsing::date_opt_from_str(due, "simple task due date", title);
+}
+
+fn parse_time_of_day(time_of_day: &str, title: &str) -> Option<TaskTimeOfDay> {
+    return match serde_json::from_value(Value::from(time_of_day)) {
+        Err(_) => {
+            logging::error(format!(
+                "Failed to convert time of day in simple task: '{}' ({})",
+                time_of_day, title
+            ));
+            None
+        }
+        Ok(time_of_day) => Some(time_of_day),
+    };
+}
+
+pub(crate) fn add_item(
+    dir_path: &Path,
+    prefix: &str,
+    title: &str,
+    note: &str,
+    due: &str,
+    time_of_day: &str,
+) -> Option<PathBuf> {
+    if title.trim().is_empty() {
+        logging::error("Title of simple task cannot be empty".to_string());
+        return None;
+    }
+    let due_date: NaiveDate = parse_due(due, title)?;
+    let time_of_day: TaskTimeOfDay = parse_time_of_day(time_of_day, title)?;
+
+    let (file_path, mut data) = match find_prefix_file(dir_path, prefix) {
+        Some(file_path) => {
+            let data: Data = types::load_to_rewrite(&file_path)?;
+            (file_path, data)
+        }
+        None => {
+            let file_stem: String = types::file_stem_of(prefix);
+            if file_stem.is_empty() {
+                logging::error(format!(
+                    "Prefix of simple task cannot be used as a file name: '{}'",
+                    prefix
+                ));
+                return None;
+            }
+            let file_path: PathBuf = dir_path.join(format!("{}.json", file_stem));
+            if file_path.exists() {
+                logging::error(format!(
+                    "Simple task file '{}' exists with another prefix",
+                    file_path.display()
+                ));
+                return None;
+            }
+            logging::info(format!(
+                "Creating simple task file '{}'",
+                file_path.display()
+            ));
+            let data: Data = Data {
+                version: types::version_current::<Data>(),
+                prefix: prefix.to_string(),
+                description: None,
+                items: Default::default(),
+            };
+            (file_path, data)
+        }
+    };
+
+    data.items.push(DataItem {
+        title: title.to_string(),
+        note: note.to_string(),
+        due: due_date.format("%Y-%m-%d").to_string(),
+        done: Default::default(),
+        snoozed_until: None,
+        time_of_day,
+        time: None,
+        duration_minutes: None,
+    });
+
+    return match types::save(&file_path, &data) {
+        true => Some(file_path),
+        false => None,
+    };
+}
+
+fn find_prefix_file(dir_path: &Path, prefix: &str) -> Option<PathBuf> {
+    let entries = match fs::read_dir(dir_path) {
+        Err(why) => {
+            logging::error(format!(
+                "Couldn't read simple task dir '{}' \n{}",
+                dir_path.display(),
+                why
+            ));
+            return None;
+        }
+        Ok(entries) => entries,
+    };
+
+    let mut file_paths: Vec<PathBuf> = entries
+        .map(|entry| entry.expect("Failed to iterate dir entry.").path())
+        .filter(|entry_path| !entry_path.is_dir())
+        .collect();
+    file_paths.sort();
+
+    let prefix_lowercase: String = prefix.to_lowercase();
+    for file_path in file_paths {
+        let is_stem_match: bool = file_path
+            .file_stem()
+            .is_some_and(|stem| stem.to_string_lossy().to_lowercase() == prefix_lowercase);
+        if let Some(data) = types::load::<Data>(&file_path) {
+            if is_stem_match || data.prefix.to_lowercase() == prefix_lowercase {
+                return Some(file_path);
+            }
+        }
+    }
+
+    return None;
+}
+
+/// Moves the done items to the archive file, which has the same format,
+/// only those done before the given date if any
+pub(crate) fn archive_done_items(
+    file_path: &Path,
+    archive_file_path: &Path,
+    done_before_opt: Option<&NaiveDate>,
+) -> Option<usize> {
+    let mut data: Data = types::load_to_rewrite(file_path)?;
+
+    let (items_done, items_active): (Vec<DataItem>, Vec<DataItem>) =
+        data.items.into_iter().partition(|item| {
+            if item.done.is_empty() {
+                return false;
+            }
+            return match done_before_opt {
+                None => true,
+                Some(done_before) => match time::parsing::date_opt_from_str(
+                    &item.done,
+                    "simple task done date",
+                    &item.title,
+                ) {
+                    None => false,
+                    Some(done) => done < *done_before,
+                },
+            };
+        });
+    data.items = items_active;
+    if items_done.is_empty() {
+        return Some(0);
+    }
+    let archived_count: usize = items_done.len();
+
+    let mut data_archive: Data = match archive_file_path.exists() {
+        true => types::load_to_rewrite(archive_file_path)?,
+        false => Data {
+            version: types::version_current::<Data>(),
+            prefix: data.prefix.clone(),
+            description: None,
+            items: Default::default(),
+        },
+    };
+    data_archive.items.extend(items_done);
+
+    // the archive first, so that no item is lost if writing fails
+    if !types::save(archive_file_path, &data_archive) {
+        return None;
+    }
+    if !types::save(file_path, &data) {
+        return None;
+    }
+    return Some(archived_count);
+}
+
+pub(crate) fn toggle_item_done(file_path: &Path, item_index: usize, date: &NaiveDate) -> bool {
+    let mut data: Data = match types::load_to_rewrite(file_path) {
+        None => {
+            return false;
+        }
+        Some(data) => data,
+    };
+
+    let item: &mut DataItem = match data.items.get_mut(item_index) {
+        None => {
+            logging::error(format!(
+                "No item with index {} in simple task file ({})",
+                item_index, data.prefix
+            ));
+            return false;
+        }
+        Some(item) => item,
+    };
+
+    if item.done.is_empty() {
+        item.done = date.format("%Y-%m-%d").to_string();
+    } else {
+        item.done.clear();
+    }
+
+    return types::save(file_path, &data);
+}
+
+pub(crate) fn set_snoozed_until(
+    file_path: &Path,
+    item_index: usize,
+    date_opt: Option<&NaiveDate>,
+) -> bool {
+    let mut data: Data = match types::load_to_rewrite(file_path) {
+        None => {
+            return false;
+        }
+        Some(data) => data,
+    };
+
+    let item: &mut DataItem = match data.items.get_mut(item_index) {
+        None => {
+            logging::error(format!(
+                "No item with index {} in simple task file ({})",
+                item_index, data.prefix
+            ));
+            return false;
+        }
+        Some(item) => item,
+    };
+
+    item.snoozed_until = date_opt.map(|date| date.format("%Y-%m-%d").to_string());
+
+    return types::save(file_path, &data);
+}
+
+/// Completion dates of the done items with the query in their title, in order
+pub(crate) fn find_done_dates(dir_path: &Path, query: &str) -> Vec<NaiveDate> {
+    let mut dates: Vec<NaiveDate> = Default::default();
+    if !dir_path.is_dir() {
+        return dates;
+    }
+
+    let query_lowercase: String = query.to_lowercase();
+    for file_path in types::file_paths_sorted(dir_path) {
+        let data: Data = match types::load::<Data>(&file_path) {
+            None => continue,
+            Some(data) => data,
+        };
+        for item in &data.items {
+            if item.done.is_empty() || !item.title.to_lowercase().contains(&query_lowercase) {
+                continue;
+            }
+            match time::parsing::date_from_str(&item.done) {
+                Err(reason) => logging::error(format!("{} ({})", reason, item.title)),
+                Ok(date) => dates.push(date),
+            }
+        }
+    }
+
+    dates.sort();
+    return dates;
+}
+
+pub(crate) fn find(dir_path: &Path, query: &str) -> Vec<(PathBuf, usize)> {
+    let mut matches: Vec<(PathBuf, usize)> = Default::default();
+
+    let entries = match fs::read_dir(dir_path) {
+        Err(why) => {
+            logging::error(format!(
+                "Couldn't read simple task dir '{}' \n{}",
+                dir_path.display(),
+                why
+            ));
+            return Default::default();
+        }
+        Ok(entries) => entries,
+    };
+
+    let query_lowercase: String = query.to_lowercase();
+    for entry in entries {
+        let entry: DirEntry = entry.expect("Failed to iterate dir entry.");
+        let entry_path: PathBuf = entry.path();
+        if entry_path.is_dir() {
+            continue;
+        }
+
+        if let Some(data) = types::load::<Data>(&entry_path) {
+            for (item_index, item) in data.items.iter().enumerate() {
+                if item.done.is_empty() && item.title.to_lowercase().contains(&query_lowercase) {
+                    matches.push((entry_path.clone(), item_index));
+                }
+            }
+        }
+    }
+
+    matches.sort();
+    return matches;
+}
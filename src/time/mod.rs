@@ -3,34 +3,47 @@
 // file, You can obtain one at http://mozilla.org/MPL/2.0/.
 
 pub mod parsing;
+pub mod recurrence;
+pub mod season;
 pub mod timestamp;
 
 use std::cmp::Ordering;
+use std::sync::RwLock;
 // dependencies
 use chrono::{DateTime, Datelike, Days, Local, Month, Months, NaiveDate, NaiveWeek, Weekday};
 
+const MSG_LOCK_FAIL_READ: &str = "Failed to acquire lock to read simulated date.";
+const MSG_LOCK_FAIL_WRITE: &str = "Failed to acquire lock to write simulated date.";
+
+static TODAY_OVERRIDE: RwLock<Option<NaiveDate>> = RwLock::new(None);
+
 const MONTHS_12: Months = Months::new(12);
 
 const DAYS_7: Days = Days::new(7);
 const DAYS_6: Days = Days::new(6);
 const DAYS_1: Days = Days::new(1);
 
-pub fn add_years(date: &NaiveDate, count: u8) -> Option<NaiveDate> {
-    return date.checked_add_months(Months::new((count * 12) as u32));
+pub fn add_years(date: &NaiveDate, count: u32) -> Option<NaiveDate> {
+    return date.checked_add_months(Months::new(count.checked_mul(12)?));
 }
 
-pub fn add_months(date: &NaiveDate, count: u8) -> Option<NaiveDate> {
-    return date.checked_add_months(Months::new(count as u32));
+pub fn add_months(date: &NaiveDate, count: u32) -> Option<NaiveDate> {
+    return date.checked_add_months(Months::new(count));
 }
 
-pub fn add_weeks(date: &NaiveDate, count: u8) -> Option<NaiveDate> {
-    return date.checked_add_days(Days::new((count * 7) as u64));
+pub fn add_weeks(date: &NaiveDate, count: u32) -> Option<NaiveDate> {
+    return date.checked_add_days(Days::new(count as u64 * 7));
 }
 
-pub fn add_days(date: &NaiveDate, count: u8) -> Option<NaiveDate> {
+pub fn add_days(date: &NaiveDate, count: u32) -> Option<NaiveDate> {
     return date.checked_add_days(Days::new(count as u64));
 }
 
+/// Calendar months from the month of one date to the month of the other, the days are ignored
+pub fn months_between(from: &NaiveDate, to: &NaiveDate) -> i64 {
+    return (to.year() - from.year()) as i64 * 12 + to.month() as i64 - from.month() as i64;
+}
+
 pub fn increment_by_one_week(date: &NaiveDate) -> NaiveDate {
     return date.checked_add_days(DAYS_7).expect("Failed to add days.");
 }
@@ -55,7 +68,15 @@ pub fn adjust_by_buffer_days(date: &NaiveDate, count: i32) -> Option<NaiveDate>
     };
 }
 
+pub fn set_today(date: NaiveDate) {
+    let mut lock = TODAY_OVERRIDE.write().expect(MSG_LOCK_FAIL_WRITE);
+    *lock = Some(date);
+}
+
 pub fn today() -> NaiveDate {
+    if let Some(date) = *TODAY_OVERRIDE.read().expect(MSG_LOCK_FAIL_READ) {
+        return date;
+    }
     let dt_now: DateTime<Local> = Local::now();
     return NaiveDate::from_ymd_opt(dt_now.year(), dt_now.month(), dt_now.day())
         .expect("Failed to create NaiveDate from now()");
@@ -108,3 +129,101 @@ pub fn weekday_abbrev(date: &NaiveDate) -> String {
 pub fn is_day_in_first_week_of_year(date: &NaiveDate) -> bool {
     return format!("{:?}", date.iso_week()).ends_with("01");
 }
+
+/// Nth weekday of a month, counted from the end of the month if negative (-1 is the last one)
+pub fn nth_weekday_of_month(
+    year: i32,
+    month: u32,
+    weekday: Weekday,
+    nth: i32,
+) -> Option<NaiveDate> {
+    if nth > 0 {
+        return NaiveDate::from_weekday_of_month_opt(year, month, weekday, u8::try_from(nth).ok()?);
+    }
+    if nth == 0 {
+        return None;
+    }
+    let mut date: NaiveDate = NaiveDate::from_ymd_opt(year, month, 1)?
+        .checked_add_months(Months::new(1))?
+        .pred_opt()?;
+    while date.weekday() != weekday {
+        date = date.pred_opt()?;
+    }
+    let date: NaiveDate = date.checked_sub_days(Days::new(7 * (nth.unsigned_abs() as u64 - 1)))?;
+    return if date.month() == month {
+        Some(date)
+    } else {
+        None
+    };
+}
+
+/// Easter Sunday in the Gregorian calendar (anonymous Gregorian algorithm)
+pub fn easter_sunday(year: i32) -> Option<NaiveDate> {
+    let a: i32 = year % 19;
+    let b: i32 = year / 100;
+    let c: i32 = year % 100;
+    let d: i32 = b / 4;
+    let e: i32 = b % 4;
+    let f: i32 = (b + 8) / 25;
+    let g: i32 = (b - f + 1) / 3;
+    let h: i32 = (19 * a + b - d - g + 15) % 30;
+    let i: i32 = c / 4;
+    let k: i32 = c % 4;
+    let l: i32 = (32 + 2 * e + 2 * i - h - k) % 7;
+    let m: i32 = (a + 11 * h + 22 * l) / 451;
+    let month: i32 = (h + l - 7 * m + 114) / 31;
+    let day: i32 = (h + l - 7 * m + 114) % 31 + 1;
+    return NaiveDate::from_ymd_opt(year, month as u32, day as u32);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        return NaiveDate::from_ymd_opt(year, month, day).expect("Invalid test date");
+    }
+
+    #[test]
+    fn easter_sunday_of_known_years() {
+        assert_eq!(easter_sunday(2000), Some(date(2000, 4, 23)));
+        assert_eq!(easter_sunday(2024), Some(date(2024, 3, 31)));
+        assert_eq!(easter_sunday(2026), Some(date(2026, 4, 5)));
+        assert_eq!(easter_sunday(2027), Some(date(2027, 3, 28)));
+        assert_eq!(easter_sunday(2038), Some(date(2038, 4, 25)));
+    }
+
+    #[test]
+    fn nth_weekday_from_the_start() {
+        assert_eq!(
+            nth_weekday_of_month(2026, 5, Weekday::Sun, 2),
+            Some(date(2026, 5, 10))
+        );
+        assert_eq!(nth_weekday_of_month(2026, 2, Weekday::Mon, 5), None);
+        assert_eq!(nth_weekday_of_month(2026, 2, Weekday::Mon, 0), None);
+    }
+
+    #[test]
+    fn nth_weekday_from_the_end() {
+        assert_eq!(
+            nth_weekday_of_month(2026, 5, Weekday::Mon, -1),
+            Some(date(2026, 5, 25))
+        );
+        assert_eq!(
+            nth_weekday_of_month(2026, 5, Weekday::Sun, -1),
+            Some(date(2026, 5, 31))
+        );
+        assert_eq!(
+            nth_weekday_of_month(2026, 2, Weekday::Fri, -2),
+            Some(date(2026, 2, 20))
+        );
+        assert_eq!(nth_weekday_of_month(2026, 2, Weekday::Fri, -5), None);
+    }
+
+    #[test]
+    fn months_between_ignores_days() {
+        assert_eq!(months_between(&date(2026, 1, 31), &date(2026, 2, 1)), 1);
+        assert_eq!(months_between(&date(2022, 1, 1), &date(2026, 10, 10)), 57);
+        assert_eq!(months_between(&date(2026, 10, 10), &date(2026, 1, 1)), -9);
+    }
+}
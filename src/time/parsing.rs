@@ -3,25 +3,36 @@
 // file, You can obtain one at http://mozilla.org/MPL/2.0/.
 
 // dependencies
-use chrono::NaiveDate;
+use chrono::{NaiveDate, NaiveTime};
 // internal
 use crate::logging;
 
-pub fn date_opt_from_ymd(
-    year: i32,
-    month: u32,
-    day: u32,
-    note_place: &str,
-    note_item: &str,
-) -> Option<NaiveDate> {
-    let date_opt: Option<NaiveDate> = NaiveDate::from_ymd_opt(year, month, day);
-    if date_opt.is_none() {
-        logging::error(format!(
-            "Failed to convert date from Y-M-D in {}: '{}'-'{}'-'{}' ({})",
-            note_place, year, month, day, note_item
-        ));
+pub fn date_from_ymd(year: i32, month: u32, day: u32) -> Result<NaiveDate, String> {
+    return match NaiveDate::from_ymd_opt(year, month, day) {
+        None => Err(format!(
+            "Failed to convert date from Y-M-D: '{}'-'{}'-'{}'",
+            year, month, day
+        )),
+        Some(date) => Ok(date),
     };
-    return date_opt;
+}
+
+pub fn date_from_str(date_string: &str) -> Result<NaiveDate, String> {
+    return NaiveDate::parse_from_str(date_string, "%Y-%m-%d").map_err(|why| {
+        format!(
+            "Failed to convert date from string: '{}' ({})",
+            date_string, why
+        )
+    });
+}
+
+pub fn time_from_str(time_string: &str) -> Result<NaiveTime, String> {
+    return NaiveTime::parse_from_str(time_string, "%H:%M").map_err(|why| {
+        format!(
+            "Failed to convert time from string: '{}' ({})",
+            time_string, why
+        )
+    });
 }
 
 pub fn date_opt_from_str(
@@ -29,16 +40,11 @@ pub fn date_opt_from_str(
     note_place: &str,
     note_item: &str,
 ) -> Option<NaiveDate> {
-    let date_opt: Option<NaiveDate> = match NaiveDate::parse_from_str(date_string, "%Y-%m-%d") {
-        Err(why) => {
-            logging::error(format!(
-                "Failed to convert date from string in {}: '{}' ({})",
-                note_place, date_string, note_item
-            ));
-            logging::error(format!("{}", why));
+    return match date_from_str(date_string) {
+        Err(reason) => {
+            logging::error(format!("{} in {} ({})", reason, note_place, note_item));
             None
         }
         Ok(date) => Some(date),
     };
-    return date_opt;
 }
@@ -0,0 +1,464 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! Subset of the iCalendar RRULE (RFC 5545): FREQ, INTERVAL, BYDAY (with ordinals),
+//! BYMONTHDAY and BYMONTH. The week starts on Monday.
+
+use std::fmt;
+// dependencies
+use chrono::{Datelike, Days, Months, NaiveDate, Weekday};
+// internal
+use crate::time;
+
+/// Periods searched for the next occurrence from the one of the date after,
+/// e.g. about 80 years of months
+const PERIOD_COUNT_MAX: u32 = 1000;
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum RecurrenceFrequency {
+    Daily,
+    Weekly,
+    Monthly,
+    Yearly,
+}
+
+#[derive(Clone, Copy)]
+pub struct RecurrenceWeekday {
+    /// Nth weekday of the month or year, counted from the end if negative
+    pub ordinal: Option<i32>,
+    pub weekday: Weekday,
+}
+
+pub struct RecurrenceRule {
+    pub frequency: RecurrenceFrequency,
+    pub interval: u32,
+    pub by_day: Vec<RecurrenceWeekday>,
+    pub by_month_day: Vec<i32>,
+    pub by_month: Vec<u32>,
+}
+
+impl RecurrenceRule {
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let text: &str = text.trim();
+        let text: &str = match text.get(..6) {
+            Some(prefix) if prefix.eq_ignore_ascii_case("RRULE:") => &text[6..],
+            _ => text,
+        };
+
+        let mut frequency_opt: Option<RecurrenceFrequency> = None;
+        let mut interval_opt: Option<u32> = None;
+        let mut by_day_opt: Option<Vec<RecurrenceWeekday>> = None;
+        let mut by_month_day_opt: Option<Vec<i32>> = None;
+        let mut by_month_opt: Option<Vec<u32>> = None;
+
+        for part in text.split(';').filter(|part| !part.is_empty()) {
+            let (key, value) = match part.split_once('=') {
+                None => return Err(format!("Expected KEY=VALUE in RRULE: '{}'", part)),
+                Some((key, value)) => (key.trim().to_uppercase(), value.trim()),
+            };
+            let is_duplicate: bool = match key.as_str() {
+                "FREQ" => frequency_opt.replace(parse_frequency(value)?).is_some(),
+                "INTERVAL" => interval_opt.replace(parse_interval(value)?).is_some(),
+                "BYDAY" => by_day_opt
+                    .replace(parse_list(value, parse_weekday)?)
+                    .is_some(),
+                "BYMONTHDAY" => by_month_day_opt
+                    .replace(parse_list(value, parse_month_day)?)
+                    .is_some(),
+                "BYMONTH" => by_month_opt
+                    .replace(parse_list(value, parse_month)?)
+                    .is_some(),
+                _ => return Err(format!("Unsupported RRULE part: '{}'", key)),
+            };
+            if is_duplicate {
+                return Err(format!("Duplicate RRULE part: '{}'", key));
+            }
+        }
+
+        let rule: RecurrenceRule = RecurrenceRule {
+            frequency: frequency_opt.ok_or("Missing FREQ in RRULE")?,
+            interval: interval_opt.unwrap_or(1),
+            by_day: by_day_opt.unwrap_or_default(),
+            by_month_day: by_month_day_opt.unwrap_or_default(),
+            by_month: by_month_opt.unwrap_or_default(),
+        };
+
+        let has_ordinals: bool = rule.by_day.iter().any(|day| day.ordinal.is_some());
+        match rule.frequency {
+            RecurrenceFrequency::Daily | RecurrenceFrequency::Weekly if has_ordinals => {
+                return Err("BYDAY ordinals need FREQ=MONTHLY or FREQ=YEARLY".to_string());
+            }
+            RecurrenceFrequency::Weekly if !rule.by_month_day.is_empty() => {
+                return Err("BYMONTHDAY cannot be used with FREQ=WEEKLY".to_string());
+            }
+            _ => {}
+        }
+        return Ok(rule);
+    }
+
+    /// First occurrence after the given date, with the periods of the interval
+    /// counted from the one of the start date
+    pub fn next_after(&self, start: &NaiveDate, after: &NaiveDate) -> Option<NaiveDate> {
+        // the periods before the one of the date after have no occurrence after it
+        let period_first: u32 = self.period_offset(start, after) / self.interval;
+        for period_index in period_first..period_first.saturating_add(PERIOD_COUNT_MAX) {
+            let period_start: NaiveDate =
+                self.period_start(start, period_index.checked_mul(self.interval)?)?;
+            let mut dates: Vec<NaiveDate> = self.expand(&period_start, start);
+            dates.sort();
+            let date_opt: Option<NaiveDate> =
+                dates.into_iter().find(|date| date > after && date >= start);
+            if date_opt.is_some() {
+                return date_opt;
+            }
+        }
+        return None;
+    }
+
+    /// Periods from the one of the start date to the one of the given date, zero before the start
+    fn period_offset(&self, start: &NaiveDate, date: &NaiveDate) -> u32 {
+        let offset: i64 = match self.frequency {
+            RecurrenceFrequency::Daily => (*date - *start).num_days(),
+            RecurrenceFrequency::Weekly => (date.week(Weekday::Mon).first_day()
+                - start.week(Weekday::Mon).first_day())
+            .num_weeks(),
+            RecurrenceFrequency::Monthly => time::months_between(start, date),
+            RecurrenceFrequency::Yearly => (date.year() - start.year()) as i64,
+        };
+        return u32::try_from(offset.max(0)).unwrap_or(u32::MAX);
+    }
+
+    fn period_start(&self, start: &NaiveDate, period_offset: u32) -> Option<NaiveDate> {
+        return match self.frequency {
+            RecurrenceFrequency::Daily => start.checked_add_days(Days::new(period_offset as u64)),
+            RecurrenceFrequency::Weekly => {
+                let monday: NaiveDate = start.week(Weekday::Mon).first_day();
+                monday.checked_add_days(Days::new(7 * period_offset as u64))
+            }
+            RecurrenceFrequency::Monthly => start
+                .with_day(1)?
+                .checked_add_months(Months::new(period_offset)),
+            RecurrenceFrequency::Yearly => {
+                NaiveDate::from_ymd_opt(start.year() + period_offset as i32, 1, 1)
+            }
+        };
+    }
+
+    fn expand(&self, period_start: &NaiveDate, start: &NaiveDate) -> Vec<NaiveDate> {
+        let dates: Vec<NaiveDate> = match self.frequency {
+            RecurrenceFrequency::Daily => {
+                let is_day_match: bool = self.by_day.is_empty()
+                    || self
+                        .by_day
+                        .iter()
+                        .any(|day| day.weekday == period_start.weekday());
+                let is_month_day_match: bool = self.by_month_day.is_empty()
+                    || self.by_month_day.iter().any(|month_day| {
+                        month_day_of(period_start, *month_day) == Some(*period_start)
+                    });
+                match is_day_match && is_month_day_match {
+                    true => vec![*period_start],
+                    false => Default::default(),
+                }
+            }
+            RecurrenceFrequency::Weekly => {
+                let weekdays: Vec<Weekday> = match self.by_day.is_empty() {
+                    true => vec![start.weekday()],
+                    false => self.by_day.iter().map(|day| day.weekday).collect(),
+                };
+                period_start
+                    .iter_days()
+                    .take(7)
+                    .filter(|date| weekdays.contains(&date.weekday()))
+                    .collect()
+            }
+            RecurrenceFrequency::Monthly => {
+                self.expand_month(period_start.year(), period_start.month(), start)
+            }
+            RecurrenceFrequency::Yearly => {
+                let year: i32 = period_start.year();
+                if !self.by_month.is_empty() {
+                    self.by_month
+                        .iter()
+                        .flat_map(|month| self.expand_month(year, *month, start))
+                        .collect()
+                } else if !self.by_day.is_empty() && self.by_month_day.is_empty() {
+                    // the ordinals count the weekdays of the whole year
+                    match NaiveDate::from_ymd_opt(year, 12, 31) {
+                        None => Default::default(),
+                        Some(last) => weekdays_between(period_start, &last, &self.by_day),
+                    }
+                } else if !self.by_month_day.is_empty() {
+                    (1..=12)
+                        .flat_map(|month| self.expand_month(year, month, start))
+                        .collect()
+                } else {
+                    NaiveDate::from_ymd_opt(year, start.month(), start.day())
+                        .into_iter()
+                        .collect()
+                }
+            }
+        };
+
+        return dates
+            .into_iter()
+            .filter(|date| self.by_month.is_empty() || self.by_month.contains(&date.month()))
+            .collect();
+    }
+
+    fn expand_month(&self, year: i32, month: u32, start: &NaiveDate) -> Vec<NaiveDate> {
+        let first: NaiveDate = match NaiveDate::from_ymd_opt(year, month, 1) {
+            None => return Default::default(),
+            Some(date) => date,
+        };
+        let last: NaiveDate = match first.checked_add_months(Months::new(1)) {
+            None => return Default::default(),
+            Some(next_first) => next_first.pred_opt().unwrap_or(first),
+        };
+
+        let month_days: Vec<NaiveDate> = self
+            .by_month_day
+            .iter()
+            .filter_map(|month_day| month_day_of(&first, *month_day))
+            .collect();
+        let weekdays: Vec<NaiveDate> = weekdays_between(&first, &last, &self.by_day);
+
+        return match (self.by_month_day.is_empty(), self.by_day.is_empty()) {
+            (true, true) => NaiveDate::from_ymd_opt(year, month, start.day())
+                .into_iter()
+                .collect(),
+            (false, true) => month_days,
+            (true, false) => weekdays,
+            (false, false) => month_days
+                .into_iter()
+                .filter(|date| weekdays.contains(date))
+                .collect(),
+        };
+    }
+}
+
+impl fmt::Display for RecurrenceRule {
+    /// Short form for the frequency column, e.g. "monthly" or "2-week"
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (name_one, name): (&str, &str) = match self.frequency {
+            RecurrenceFrequency::Daily => ("daily", "day"),
+            RecurrenceFrequency::Weekly => ("weekly", "week"),
+            RecurrenceFrequency::Monthly => ("monthly", "month"),
+            RecurrenceFrequency::Yearly => ("yearly", "year"),
+        };
+        return match self.interval {
+            1 => write!(f, "{}", name_one),
+            interval => write!(f, "{}-{}", interval, name),
+        };
+    }
+}
+
+/// Day of the month of the given date, counted from the end if negative
+fn month_day_of(date: &NaiveDate, month_day: i32) -> Option<NaiveDate> {
+    let first: NaiveDate = date.with_day(1)?;
+    if month_day > 0 {
+        return first.with_day(month_day as u32);
+    }
+    let last: NaiveDate = first.checked_add_months(Months::new(1))?.pred_opt()?;
+    let date_opt: Option<NaiveDate> =
+        last.checked_sub_days(Days::new(month_day.unsigned_abs() as u64 - 1));
+    return date_opt.filter(|date| date.month() == first.month());
+}
+
+fn weekdays_between(
+    first: &NaiveDate,
+    last: &NaiveDate,
+    by_day: &[RecurrenceWeekday],
+) -> Vec<NaiveDate> {
+    let mut dates: Vec<NaiveDate> = Default::default();
+    for day in by_day {
+        let matches: Vec<NaiveDate> = first
+            .iter_days()
+            .take_while(|date| date <= last)
+            .filter(|date| date.weekday() == day.weekday)
+            .collect();
+        let selected_opt: Option<NaiveDate> = match day.ordinal {
+            None => {
+                dates.extend(matches);
+                continue;
+            }
+            Some(ordinal) if ordinal > 0 => matches.get(ordinal as usize - 1).copied(),
+            Some(ordinal) => matches
+                .len()
+                .checked_sub(ordinal.unsigned_abs() as usize)
+                .and_then(|index| matches.get(index).copied()),
+        };
+        dates.extend(selected_opt);
+    }
+    return dates;
+}
+
+fn parse_frequency(value: &str) -> Result<RecurrenceFrequency, String> {
+    return match value.to_uppercase().as_str() {
+        "DAILY" => Ok(RecurrenceFrequency::Daily),
+        "WEEKLY" => Ok(RecurrenceFrequency::Weekly),
+        "MONTHLY" => Ok(RecurrenceFrequency::Monthly),
+        "YEARLY" => Ok(RecurrenceFrequency::Yearly),
+        _ => Err(format!("Unsupported FREQ in RRULE: '{}'", value)),
+    };
+}
+
+fn parse_interval(value: &str) -> Result<u32, String> {
+    return match value.parse::<u32>() {
+        Ok(interval) if interval > 0 => Ok(interval),
+        _ => Err(format!("INTERVAL must be a positive number: '{}'", value)),
+    };
+}
+
+fn parse_list<T>(value: &str, parse: fn(&str) -> Result<T, String>) -> Result<Vec<T>, String> {
+    return value.split(',').map(|item| parse(item.trim())).collect();
+}
+
+fn parse_weekday(value: &str) -> Result<RecurrenceWeekday, String> {
+    let error: String = format!("Invalid BYDAY in RRULE: '{}'", value);
+    if value.len() < 2 || !value.is_char_boundary(value.len() - 2) {
+        return Err(error);
+    }
+    let (ordinal_text, weekday_text) = value.split_at(value.len() - 2);
+    let weekday: Weekday = match weekday_text.to_uppercase().as_str() {
+        "MO" => Weekday::Mon,
+        "TU" => Weekday::Tue,
+        "WE" => Weekday::Wed,
+        "TH" => Weekday::Thu,
+        "FR" => Weekday::Fri,
+        "SA" => Weekday::Sat,
+        "SU" => Weekday::Sun,
+        _ => return Err(error),
+    };
+    let ordinal: Option<i32> = match ordinal_text {
+        "" => None,
+        _ => match ordinal_text.parse::<i32>() {
+            Ok(ordinal) if ordinal != 0 && ordinal.abs() <= 53 => Some(ordinal),
+            _ => return Err(error),
+        },
+    };
+    return Ok(RecurrenceWeekday { ordinal, weekday });
+}
+
+fn parse_month_day(value: &str) -> Result<i32, String> {
+    return match value.parse::<i32>() {
+        Ok(month_day) if month_day != 0 && month_day.abs() <= 31 => Ok(month_day),
+        _ => Err(format!("Invalid BYMONTHDAY in RRULE: '{}'", value)),
+    };
+}
+
+fn parse_month(value: &str) -> Result<u32, String> {
+    return match value.parse::<u32>() {
+        Ok(month) if (1..=12).contains(&month) => Ok(month),
+        _ => Err(format!("Invalid BYMONTH in RRULE: '{}'", value)),
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        return NaiveDate::from_ymd_opt(year, month, day).expect("Invalid test date");
+    }
+
+    fn next_dates(rrule: &str, start: NaiveDate, count: usize) -> Vec<NaiveDate> {
+        let rule: RecurrenceRule = RecurrenceRule::parse(rrule).expect("Invalid test RRULE");
+        let mut dates: Vec<NaiveDate> = Vec::new();
+        let mut after: NaiveDate = start.pred_opt().expect("Invalid test date");
+        for _ in 0..count {
+            after = rule.next_after(&start, &after).expect("No next occurrence");
+            dates.push(after);
+        }
+        return dates;
+    }
+
+    #[test]
+    fn second_tuesday_of_the_month() {
+        assert_eq!(
+            next_dates("FREQ=MONTHLY;BYDAY=2TU", date(2026, 1, 1), 3),
+            vec![date(2026, 1, 13), date(2026, 2, 10), date(2026, 3, 10)]
+        );
+    }
+
+    #[test]
+    fn last_friday_of_the_month() {
+        assert_eq!(
+            next_dates("FREQ=MONTHLY;BYDAY=-1FR", date(2026, 1, 1), 3),
+            vec![date(2026, 1, 30), date(2026, 2, 27), date(2026, 3, 27)]
+        );
+    }
+
+    #[test]
+    fn first_and_fifteenth_of_the_month() {
+        assert_eq!(
+            next_dates("FREQ=MONTHLY;BYMONTHDAY=1,15", date(2026, 1, 10), 3),
+            vec![date(2026, 1, 15), date(2026, 2, 1), date(2026, 2, 15)]
+        );
+    }
+
+    #[test]
+    fn weekdays() {
+        assert_eq!(
+            next_dates("FREQ=WEEKLY;BYDAY=MO,TU,WE,TH,FR", date(2026, 10, 16), 3),
+            vec![date(2026, 10, 16), date(2026, 10, 19), date(2026, 10, 20)]
+        );
+    }
+
+    #[test]
+    fn month_end_start_skips_shorter_months() {
+        assert_eq!(
+            next_dates("FREQ=MONTHLY", date(2026, 1, 31), 3),
+            vec![date(2026, 1, 31), date(2026, 3, 31), date(2026, 5, 31)]
+        );
+    }
+
+    #[test]
+    fn last_day_of_the_month() {
+        assert_eq!(
+            next_dates("FREQ=MONTHLY;BYMONTHDAY=-1", date(2026, 1, 31), 3),
+            vec![date(2026, 1, 31), date(2026, 2, 28), date(2026, 3, 31)]
+        );
+    }
+
+    #[test]
+    fn february_29_start_in_leap_years_only() {
+        assert_eq!(
+            next_dates("FREQ=YEARLY", date(2024, 2, 29), 2),
+            vec![date(2024, 2, 29), date(2028, 2, 29)]
+        );
+    }
+
+    #[test]
+    fn anchor_far_in_the_past() {
+        let rule: RecurrenceRule = RecurrenceRule::parse("FREQ=DAILY").expect("Invalid RRULE");
+        assert_eq!(
+            rule.next_after(&date(2022, 1, 1), &date(2026, 10, 10)),
+            Some(date(2026, 10, 11))
+        );
+
+        let rule: RecurrenceRule =
+            RecurrenceRule::parse("FREQ=WEEKLY;INTERVAL=2").expect("Invalid RRULE");
+        assert_eq!(
+            rule.next_after(&date(1990, 1, 1), &date(2026, 10, 10)),
+            Some(date(2026, 10, 19))
+        );
+    }
+
+    #[test]
+    fn interval_counted_from_the_start() {
+        assert_eq!(
+            next_dates("FREQ=MONTHLY;INTERVAL=3;BYDAY=1MO", date(2026, 2, 1), 3),
+            vec![date(2026, 2, 2), date(2026, 5, 4), date(2026, 8, 3)]
+        );
+    }
+
+    #[test]
+    fn invalid_rules() {
+        assert!(RecurrenceRule::parse("INTERVAL=2").is_err());
+        assert!(RecurrenceRule::parse("FREQ=WEEKLY;BYDAY=2TU").is_err());
+        assert!(RecurrenceRule::parse("FREQ=MONTHLY;BYMONTHDAY=0").is_err());
+        assert!(RecurrenceRule::parse("FREQ=MONTHLY;FREQ=DAILY").is_err());
+    }
+}
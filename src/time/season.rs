@@ -0,0 +1,99 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! Window of the year given by month/day bounds, e.g. 04-01 to 09-30,
+//! or 11-01 to 03-31 over the turn of the year. Both bounds are included.
+
+// dependencies
+use chrono::{Datelike, Days, NaiveDate};
+
+/// Any day of the year is reached in this many days
+const DAY_COUNT_MAX: u64 = 366;
+
+pub struct Season {
+    from: (u32, u32),
+    to: (u32, u32),
+}
+
+impl Season {
+    pub fn new(from: (u32, u32), to: (u32, u32)) -> Self {
+        return Self { from, to };
+    }
+
+    pub fn contains(&self, date: &NaiveDate) -> bool {
+        let month_day: (u32, u32) = (date.month(), date.day());
+        return if self.from <= self.to {
+            self.from <= month_day && month_day <= self.to
+        } else {
+            month_day >= self.from || month_day <= self.to
+        };
+    }
+
+    /// The date itself when in season, otherwise the start of the next season
+    pub fn first_day_from(&self, date: &NaiveDate) -> Option<NaiveDate> {
+        for day_offset in 0..=DAY_COUNT_MAX {
+            let day: NaiveDate = date.checked_add_days(Days::new(day_offset))?;
+            if self.contains(&day) {
+                return Some(day);
+            }
+        }
+        return None;
+    }
+}
+
+/// Month and day from 'MM-DD', Feb 29 included
+pub fn month_day_from_str(text: &str) -> Result<(u32, u32), String> {
+    // any leap year
+    return match NaiveDate::parse_from_str(&format!("2000-{}", text), "%Y-%m-%d") {
+        Err(why) => Err(format!(
+            "Failed to convert month and day from string: '{}' ({})",
+            text, why
+        )),
+        Ok(date) => Ok((date.month(), date.day())),
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        return NaiveDate::from_ymd_opt(year, month, day).expect("Invalid test date");
+    }
+
+    #[test]
+    fn season_within_the_year() {
+        let season: Season = Season::new((4, 1), (9, 30));
+        assert!(season.contains(&date(2026, 4, 1)));
+        assert!(season.contains(&date(2026, 9, 30)));
+        assert!(!season.contains(&date(2026, 10, 1)));
+        assert_eq!(
+            season.first_day_from(&date(2026, 10, 17)),
+            Some(date(2027, 4, 1))
+        );
+    }
+
+    #[test]
+    fn season_over_the_new_year() {
+        let season: Season = Season::new((11, 1), (3, 31));
+        assert!(season.contains(&date(2026, 12, 31)));
+        assert!(season.contains(&date(2027, 1, 1)));
+        assert!(!season.contains(&date(2026, 4, 1)));
+        assert_eq!(
+            season.first_day_from(&date(2026, 10, 17)),
+            Some(date(2026, 11, 1))
+        );
+        assert_eq!(
+            season.first_day_from(&date(2027, 2, 1)),
+            Some(date(2027, 2, 1))
+        );
+    }
+
+    #[test]
+    fn month_day_parsing() {
+        assert_eq!(month_day_from_str("02-29"), Ok((2, 29)));
+        assert!(month_day_from_str("13-45").is_err());
+        assert!(month_day_from_str("02-30").is_err());
+    }
+}
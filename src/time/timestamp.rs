@@ -67,3 +67,8 @@ pub fn current_clock() -> String {
     let date: DateTime<Local> = Local::now();
     return format!("{}", date.format("%H:%M:%S"));
 }
+
+pub fn current_for_file_name() -> String {
+    let date: DateTime<Local> = Local::now();
+    return format!("{}", date.format("%Y%m%d-%H%M%S"));
+}
@@ -12,8 +12,16 @@ pub(crate) const INACTIVE: &str = "inactive";
 
 pub(crate) const QUIT_NOTE: &str = "press 'q' to quit";
 
+pub(crate) const STATUS_TOGGLED: &str = "toggled:";
+pub(crate) const STATUS_TOGGLE_FAILED: &str = "failed to toggle:";
+pub(crate) const STATUS_SNOOZED: &str = "snoozed until";
+pub(crate) const STATUS_UNSNOOZED: &str = "snooze cleared:";
+pub(crate) const STATUS_SNOOZE_FAILED: &str = "failed to snooze:";
+
 pub(crate) const TITLE_OVERDUE: &str = "overdue";
 pub(crate) const TITLE_TODAY: &str = "today";
 pub(crate) const NOTE_DONE_FOR_TODAY: &str = "All done for today :)";
 pub(crate) const TITLE_REST_OF_THE_WEEK: &str = "rest of the week";
 pub(crate) const TITLE_LATER_AND_OTHER: &str = "later & other";
+pub(crate) const TITLE_HISTORY: &str = "history";
+pub(crate) const NOTE_NO_HISTORY: &str = "No completion history yet";